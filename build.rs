@@ -10,6 +10,17 @@ fn main() {
         use_feature_or_nothing("wasi_ext");
     }
 
+    // `From<OwnedFd>` for the std `Child*` types, which the `tokio` impls of
+    // `AsyncFilelikeViewType` for its `Child*` types use, requires Rust 1.74.
+    if var("CARGO_FEATURE_TOKIO").is_ok()
+        && var("CARGO_CFG_UNIX").is_ok()
+        && can_compile(
+            "pub fn f(fd: std::os::fd::OwnedFd) -> std::process::ChildStdin { fd.into() }",
+        )
+    {
+        use_feature("child_stdio_from_owned_fd");
+    }
+
     // Don't rerun this on changes other than build.rs, as we only depend on
     // the rustc version.
     println!("cargo:rerun-if-changed=build.rs");
@@ -0,0 +1,336 @@
+//! Derive macros for io-lifetimes' portability traits.
+//!
+//! These are re-exported by io-lifetimes when its `derive` feature is
+//! enabled. Each derive emits the std trait impls which provide the
+//! corresponding portability trait, for both Unix-like platforms and Windows,
+//! forwarding to one field of a struct:
+//!
+//! | Derive           | Unix-like       | Windows              |
+//! | ---------------- | --------------- | -------------------- |
+//! | `AsFilelike`     | `AsFd`          | `AsHandle`           |
+//! | `AsSocketlike`   | `AsFd`          | `AsSocket`           |
+//! | `IntoFilelike`   | `Into<OwnedFd>` | `Into<OwnedHandle>`  |
+//! | `IntoSocketlike` | `Into<OwnedFd>` | `Into<OwnedSocket>`  |
+//! | `FromFilelike`   | `From<OwnedFd>` | `From<OwnedHandle>`  |
+//! | `FromSocketlike` | `From<OwnedFd>` | `From<OwnedSocket>`  |
+//!
+//! Since the filelike and socketlike derives emit the same impls on Unix-like
+//! platforms, a struct should derive from only one of the two families.
+//!
+//! The field is the struct's only field, or the field annotated with
+//! `#[fd]`. `FromFilelike` and `FromSocketlike` initialize any other fields
+//! with `Default::default()`.
+//!
+//! Adding `#[fd(unsafe_view_type)]` to the struct makes `FromFilelike` and
+//! `FromSocketlike` also emit `unsafe impl FilelikeViewType` and
+//! `unsafe impl SocketlikeViewType`, respectively. This asserts the safety
+//! contract of those traits: that the field's type returns the same resource
+//! from `Into` that was passed to its `From`, and that the struct has no
+//! other state that would be lost or leaked when a view is dropped.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use io_lifetimes::{AsFilelike, FromFilelike, IntoFilelike, OwnedFilelike};
+//!
+//! #[derive(AsFilelike, IntoFilelike, FromFilelike)]
+//! #[fd(unsafe_view_type)]
+//! struct Thing {
+//!     #[fd]
+//!     filelike: OwnedFilelike,
+//!     reads: u64,
+//! }
+//! ```
+
+#![deny(missing_docs)]
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Index, Member};
+
+/// Which family of portability traits a derive is for.
+#[derive(Clone, Copy)]
+enum Family {
+    Filelike,
+    Socketlike,
+}
+
+impl Family {
+    /// The Windows owned and borrowed types and the `As*` trait and method
+    /// for this family.
+    fn windows(self) -> (TokenStream, TokenStream, TokenStream, TokenStream) {
+        match self {
+            Self::Filelike => (
+                quote!(::io_lifetimes::OwnedHandle),
+                quote!(::io_lifetimes::BorrowedHandle),
+                quote!(::io_lifetimes::AsHandle),
+                quote!(as_handle),
+            ),
+            Self::Socketlike => (
+                quote!(::io_lifetimes::OwnedSocket),
+                quote!(::io_lifetimes::BorrowedSocket),
+                quote!(::io_lifetimes::AsSocket),
+                quote!(as_socket),
+            ),
+        }
+    }
+
+    /// The view marker trait for this family.
+    fn view_type(self) -> TokenStream {
+        match self {
+            Self::Filelike => quote!(::io_lifetimes::views::FilelikeViewType),
+            Self::Socketlike => quote!(::io_lifetimes::views::SocketlikeViewType),
+        }
+    }
+}
+
+/// The parsed input to a derive: the struct, and the field holding the
+/// resource.
+struct Input {
+    input: DeriveInput,
+    member: Member,
+    others: Vec<Member>,
+    unsafe_view_type: bool,
+}
+
+impl Input {
+    fn parse(input: DeriveInput) -> syn::Result<Self> {
+        let mut unsafe_view_type = false;
+        for attr in &input.attrs {
+            if attr.path().is_ident("fd") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("unsafe_view_type") {
+                        unsafe_view_type = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("unrecognized `fd` attribute"))
+                    }
+                })?;
+            }
+        }
+
+        let fields = match &input.data {
+            Data::Struct(data) => &data.fields,
+            _ => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "io-lifetimes derives only support structs",
+                ))
+            }
+        };
+
+        let members: Vec<(Member, bool)> = match fields {
+            Fields::Named(named) => named
+                .named
+                .iter()
+                .map(|field| {
+                    let ident = field.ident.clone().unwrap();
+                    (Member::Named(ident), has_fd_attr(&field.attrs))
+                })
+                .collect(),
+            Fields::Unnamed(unnamed) => unnamed
+                .unnamed
+                .iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    let index = Index {
+                        index: i as u32,
+                        span: field.span(),
+                    };
+                    (Member::Unnamed(index), has_fd_attr(&field.attrs))
+                })
+                .collect(),
+            Fields::Unit => Vec::new(),
+        };
+
+        let marked = members.iter().filter(|(_, marked)| *marked).count();
+        let index = match (marked, members.len()) {
+            (1, _) => members.iter().position(|(_, marked)| *marked).unwrap(),
+            (0, 1) => 0,
+            (0, _) => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "expected exactly one field, or one field annotated with `#[fd]`",
+                ))
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "only one field may be annotated with `#[fd]`",
+                ))
+            }
+        };
+
+        let mut members: Vec<Member> = members.into_iter().map(|(member, _)| member).collect();
+        let member = members.remove(index);
+
+        Ok(Self {
+            input,
+            member,
+            others: members,
+            unsafe_view_type,
+        })
+    }
+
+    fn expand_as(&self, family: Family) -> TokenStream {
+        let (_, windows_borrowed, windows_trait, windows_method) = family.windows();
+        let name = &self.input.ident;
+        let (impl_generics, ty_generics, where_clause) = self.input.generics.split_for_impl();
+        let member = &self.member;
+
+        quote! {
+            #[cfg(any(unix, target_os = "wasi", target_os = "hermit"))]
+            impl #impl_generics ::io_lifetimes::AsFd for #name #ty_generics #where_clause {
+                #[inline]
+                fn as_fd(&self) -> ::io_lifetimes::BorrowedFd<'_> {
+                    ::io_lifetimes::AsFd::as_fd(&self.#member)
+                }
+            }
+
+            #[cfg(windows)]
+            impl #impl_generics #windows_trait for #name #ty_generics #where_clause {
+                #[inline]
+                fn #windows_method(&self) -> #windows_borrowed<'_> {
+                    #windows_trait::#windows_method(&self.#member)
+                }
+            }
+        }
+    }
+
+    fn expand_into(&self, family: Family) -> TokenStream {
+        let (windows_owned, _, _, _) = family.windows();
+        let name = &self.input.ident;
+        let (impl_generics, ty_generics, where_clause) = self.input.generics.split_for_impl();
+        let member = &self.member;
+
+        quote! {
+            #[cfg(any(unix, target_os = "wasi", target_os = "hermit"))]
+            impl #impl_generics ::core::convert::From<#name #ty_generics>
+                for ::io_lifetimes::OwnedFd #where_clause
+            {
+                #[inline]
+                fn from(owned: #name #ty_generics) -> Self {
+                    ::core::convert::From::from(owned.#member)
+                }
+            }
+
+            #[cfg(windows)]
+            impl #impl_generics ::core::convert::From<#name #ty_generics>
+                for #windows_owned #where_clause
+            {
+                #[inline]
+                fn from(owned: #name #ty_generics) -> Self {
+                    ::core::convert::From::from(owned.#member)
+                }
+            }
+        }
+    }
+
+    fn expand_from(&self, family: Family) -> TokenStream {
+        let (windows_owned, _, _, _) = family.windows();
+        let name = &self.input.ident;
+        let (impl_generics, ty_generics, where_clause) = self.input.generics.split_for_impl();
+        let member = &self.member;
+        let others = &self.others;
+
+        let view_type = if self.unsafe_view_type {
+            let view_type = family.view_type();
+            quote! {
+                // Safety: The user has acknowledged the `ViewType` safety
+                // contract with `#[fd(unsafe_view_type)]`.
+                unsafe impl #impl_generics #view_type for #name #ty_generics #where_clause {}
+            }
+        } else {
+            TokenStream::new()
+        };
+
+        quote! {
+            #[cfg(any(unix, target_os = "wasi", target_os = "hermit"))]
+            impl #impl_generics ::core::convert::From<::io_lifetimes::OwnedFd>
+                for #name #ty_generics #where_clause
+            {
+                #[inline]
+                fn from(owned: ::io_lifetimes::OwnedFd) -> Self {
+                    Self {
+                        #member: ::core::convert::From::from(owned),
+                        #(#others: ::core::default::Default::default(),)*
+                    }
+                }
+            }
+
+            #[cfg(windows)]
+            impl #impl_generics ::core::convert::From<#windows_owned>
+                for #name #ty_generics #where_clause
+            {
+                #[inline]
+                fn from(owned: #windows_owned) -> Self {
+                    Self {
+                        #member: ::core::convert::From::from(owned),
+                        #(#others: ::core::default::Default::default(),)*
+                    }
+                }
+            }
+
+            #view_type
+        }
+    }
+}
+
+fn has_fd_attr(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident("fd"))
+}
+
+fn derive(
+    input: proc_macro::TokenStream,
+    expand: impl FnOnce(&Input) -> TokenStream,
+) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match Input::parse(input) {
+        Ok(input) => expand(&input).into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Derive `AsFd` on Unix-like platforms and `AsHandle` on Windows, which
+/// provide `AsFilelike`.
+#[proc_macro_derive(AsFilelike, attributes(fd))]
+pub fn derive_as_filelike(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive(input, |input| input.expand_as(Family::Filelike))
+}
+
+/// Derive `AsFd` on Unix-like platforms and `AsSocket` on Windows, which
+/// provide `AsSocketlike`.
+#[proc_macro_derive(AsSocketlike, attributes(fd))]
+pub fn derive_as_socketlike(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive(input, |input| input.expand_as(Family::Socketlike))
+}
+
+/// Derive `Into<OwnedFd>` on Unix-like platforms and `Into<OwnedHandle>` on
+/// Windows, which provide `IntoFilelike`.
+#[proc_macro_derive(IntoFilelike, attributes(fd))]
+pub fn derive_into_filelike(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive(input, |input| input.expand_into(Family::Filelike))
+}
+
+/// Derive `Into<OwnedFd>` on Unix-like platforms and `Into<OwnedSocket>` on
+/// Windows, which provide `IntoSocketlike`.
+#[proc_macro_derive(IntoSocketlike, attributes(fd))]
+pub fn derive_into_socketlike(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive(input, |input| input.expand_into(Family::Socketlike))
+}
+
+/// Derive `From<OwnedFd>` on Unix-like platforms and `From<OwnedHandle>` on
+/// Windows, which provide `FromFilelike`, and optionally `FilelikeViewType`.
+#[proc_macro_derive(FromFilelike, attributes(fd))]
+pub fn derive_from_filelike(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive(input, |input| input.expand_from(Family::Filelike))
+}
+
+/// Derive `From<OwnedFd>` on Unix-like platforms and `From<OwnedSocket>` on
+/// Windows, which provide `FromSocketlike`, and optionally
+/// `SocketlikeViewType`.
+#[proc_macro_derive(FromSocketlike, attributes(fd))]
+pub fn derive_from_socketlike(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive(input, |input| input.expand_from(Family::Socketlike))
+}
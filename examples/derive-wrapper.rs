@@ -0,0 +1,37 @@
+//! The same wrapper as in the `owning-wrapper` example, with the traits
+//! derived instead of implemented by hand.
+
+use io_lifetimes::{AsFilelike, FromFilelike, IntoFilelike, OwnedFilelike};
+
+/// A wrapper around a file descriptor.
+///
+/// The derives emit `AsFd`, `Into<OwnedFd>`, and `From<OwnedFd>` on
+/// Unix-like platforms, and `AsHandle`, `Into<OwnedHandle>`, and
+/// `From<OwnedHandle>` on Windows, forwarding to the `#[fd]` field.
+/// `#[fd(unsafe_view_type)]` additionally declares that `Thing` can be used
+/// in a `FilelikeView`, which is sound because `OwnedFilelike` gives back the
+/// same resource it was constructed from, and `reads` has no resources of its
+/// own.
+#[derive(AsFilelike, IntoFilelike, FromFilelike)]
+#[fd(unsafe_view_type)]
+struct Thing {
+    #[fd]
+    filelike: OwnedFilelike,
+    reads: u64,
+}
+
+fn main() {
+    // The derived impls make the blanket impls for the portable `Filelike`
+    // traits available.
+    let file = std::fs::File::open("Cargo.toml").unwrap();
+    let thing = Thing::from_into_filelike(file);
+    assert_eq!(thing.reads, 0);
+    let _ = thing.as_filelike();
+
+    // And `Thing` can be used as the target of a view.
+    let view = thing.as_filelike_view::<Thing>();
+    assert_eq!(view.reads, 0);
+    drop(view);
+
+    let _ = thing.into_filelike();
+}
@@ -22,7 +22,8 @@ fn main() -> io::Result<()> {
     // you do anything that you couldn't already do with `ChildStderr` etc., but
     // it's useful outside of standard library types.
     let mut buffer = String::new();
-    file.read_to_string(&mut buffer)?;
+    let result = file.read_to_string(&mut buffer);
 
-    Ok(())
+    child.wait()?;
+    result.map(|_| ())
 }
@@ -0,0 +1,62 @@
+/*
+ * io_lifetimes.h - I/O ownership annotations for C interfaces.
+ *
+ * These typedefs let C headers say whether a file descriptor argument or
+ * return value is owned or borrowed, using the same conventions as the Rust
+ * io-lifetimes crate and `std::os::fd`. Each typedef is an `int`, so they
+ * are ABI-compatible with plain file descriptors, and with the Rust types
+ * listed below.
+ *
+ *   C type                 Rust type                          Meaning
+ *   ---------------------  ---------------------------------  ---------------
+ *   owned_fd               OwnedFd                            owned, never -1
+ *   borrowed_fd            BorrowedFd<'_>                     borrowed, never -1
+ *   nullable_owned_fd      Option<OwnedFd>,                   owned, or -1
+ *                          io_lifetimes::c_api::NullableOwnedFd
+ *   nullable_borrowed_fd   Option<BorrowedFd<'_>>,            borrowed, or -1
+ *                          io_lifetimes::c_api::NullableBorrowedFd<'_>
+ *
+ * Ownership rules:
+ *
+ *  - An `owned_fd` argument transfers ownership from the caller to the
+ *    callee, which becomes responsible for closing it. The caller must not
+ *    use or close it after the call.
+ *
+ *  - An `owned_fd` return value transfers ownership from the callee to the
+ *    caller, which becomes responsible for closing it.
+ *
+ *  - A `borrowed_fd` argument is only valid for the duration of the call.
+ *    The callee must not close it, and must not retain it after returning,
+ *    though it may duplicate it with `dup` to get an owned file descriptor.
+ *
+ *  - A `borrowed_fd` return value is borrowed from something which the
+ *    function's documentation must identify, such as one of its arguments,
+ *    and is valid only as long as that is.
+ *
+ *  - The `nullable_` variants follow the same rules, except that they may
+ *    be `IO_LIFETIMES_NO_FD` (-1) to indicate no file descriptor, such as to
+ *    report an error. A `-1` value owns nothing and must not be closed.
+ *
+ * Non-nullable types must never hold -1; Rust code relies on this, and
+ * treats -1 in an `OwnedFd` or `BorrowedFd` as undefined behavior.
+ */
+
+#ifndef IO_LIFETIMES_H
+#define IO_LIFETIMES_H
+
+/* The value of a nullable file descriptor which holds no file descriptor. */
+#define IO_LIFETIMES_NO_FD (-1)
+
+/* An owned file descriptor, which is never -1. */
+typedef int owned_fd;
+
+/* A borrowed file descriptor, which is never -1. */
+typedef int borrowed_fd;
+
+/* An owned file descriptor, or IO_LIFETIMES_NO_FD. */
+typedef int nullable_owned_fd;
+
+/* A borrowed file descriptor, or IO_LIFETIMES_NO_FD. */
+typedef int nullable_borrowed_fd;
+
+#endif /* IO_LIFETIMES_H */
@@ -0,0 +1,323 @@
+//! File descriptors typed by their access mode.
+//!
+//! [`ReadFd`], [`WriteFd`], and [`ReadWriteFd`] are owned file descriptors
+//! which are known to be open for reading, writing, or both, and
+//! [`BorrowedReadFd`], [`BorrowedWriteFd`], and [`BorrowedReadWriteFd`] are
+//! their borrowed counterparts. Functions can take these in place of
+//! [`OwnedFd`] and [`BorrowedFd`] to document and enforce how they'll use a
+//! file descriptor, so that a file descriptor with the wrong access mode is
+//! rejected when it's converted, rather than when it's first used.
+//!
+//! They're constructed from plain file descriptors with a runtime check of
+//! the access mode, as reported by `fcntl(F_GETFL)`, and can be converted
+//! back into plain file descriptors freely. Read-write file descriptors can
+//! also be narrowed into read-only or write-only ones.
+//!
+//! # Example
+//!
+//! ```rust
+//! use io_lifetimes::access::{BorrowedReadFd, ReadFd, WriteFd};
+//! use io_lifetimes::OwnedFd;
+//! use std::fs::File;
+//!
+//! fn first_byte(fd: BorrowedReadFd<'_>) -> u8 {
+//!     // ...
+//! #   0
+//! }
+//!
+//! let file = ReadFd::try_from(OwnedFd::from(File::open("Cargo.toml")?))?;
+//! first_byte(file.as_borrowed());
+//!
+//! // A file descriptor opened for reading isn't writable.
+//! assert!(WriteFd::try_from(OwnedFd::from(file)).is_err());
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! [`OwnedFd`]: https://doc.rust-lang.org/stable/std/os/fd/struct.OwnedFd.html
+//! [`BorrowedFd`]: https://doc.rust-lang.org/stable/std/os/fd/struct.BorrowedFd.html
+
+use crate::open_fds::AccessMode;
+use crate::raw::RawFilelike;
+use crate::{inspect, AsFd, BorrowedFd, BorrowedFilelike, OwnedFd, OwnedFilelike};
+use std::fmt;
+use std::io;
+use std::os::unix::io::{AsRawFd, IntoRawFd};
+
+/// The error returned when a file descriptor is rejected by a check, such as
+/// of its access mode, which holds the file descriptor so that it isn't lost.
+///
+/// `E` is the reason for the rejection. The typed wrappers in this module use
+/// an [`io::Error`], and those in [`typed`] use a [`TryViewError`].
+///
+/// [`typed`]: crate::typed
+/// [`TryViewError`]: crate::views::TryViewError
+pub struct RejectedFd<Fd, E = io::Error> {
+    fd: Fd,
+    error: E,
+}
+
+impl<Fd, E> RejectedFd<Fd, E> {
+    #[inline]
+    pub(crate) fn new(fd: Fd, error: E) -> Self {
+        Self { fd, error }
+    }
+
+    /// Return the file descriptor which failed the check.
+    #[inline]
+    pub fn into_fd(self) -> Fd {
+        self.fd
+    }
+
+    /// Return the reason the check failed. For the typed wrappers in this
+    /// module, this has the kind [`io::ErrorKind::InvalidInput`] if the
+    /// file descriptor's access mode didn't match.
+    #[inline]
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+}
+
+impl<Fd, E: fmt::Debug> fmt::Debug for RejectedFd<Fd, E> {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RejectedFd")
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Fd, E: fmt::Display> fmt::Display for RejectedFd<Fd, E> {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl<Fd, E: std::error::Error + 'static> std::error::Error for RejectedFd<Fd, E> {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        // `Display` shows the reason itself, so skip over it.
+        self.error.source()
+    }
+}
+
+impl<Fd, E: Into<io::Error>> From<RejectedFd<Fd, E>> for io::Error {
+    #[inline]
+    fn from(err: RejectedFd<Fd, E>) -> Self {
+        err.error.into()
+    }
+}
+
+/// Check that `fd`'s access mode satisfies `allowed`.
+fn check(
+    fd: BorrowedFd<'_>,
+    allowed: fn(AccessMode) -> bool,
+    reason: &'static str,
+) -> io::Result<()> {
+    match inspect::access_mode(fd)? {
+        Some(mode) if allowed(mode) => Ok(()),
+        _ => Err(io::Error::new(io::ErrorKind::InvalidInput, reason)),
+    }
+}
+
+macro_rules! access_fd {
+    ($owned:ident, $borrowed:ident, $desc:literal, $allowed:expr, $reason:literal) => {
+        #[doc = concat!("An owned file descriptor which is open for ", $desc, ".")]
+        #[derive(Debug)]
+        #[repr(transparent)]
+        pub struct $owned(OwnedFd);
+
+        impl $owned {
+            #[doc = concat!("Check that `owned` is open for ", $desc, ", and wrap it.")]
+            ///
+            /// On failure, the returned error holds `owned`.
+            #[inline]
+            pub fn try_from_filelike(owned: OwnedFilelike) -> Result<Self, RejectedFd<OwnedFd>> {
+                match check(owned.as_fd(), $allowed, $reason) {
+                    Ok(()) => Ok(Self(owned)),
+                    Err(error) => Err(RejectedFd::new(owned, error)),
+                }
+            }
+
+            #[doc = concat!("Wrap `owned` without checking that it's open for ", $desc, ".")]
+            ///
+            /// # Safety
+            ///
+            #[doc = concat!("`owned` must be open for ", $desc, ".")]
+            #[inline]
+            pub unsafe fn from_filelike_unchecked(owned: OwnedFilelike) -> Self {
+                Self(owned)
+            }
+
+            /// Borrow the file descriptor, keeping its access mode.
+            #[inline]
+            pub fn as_borrowed(&self) -> $borrowed<'_> {
+                $borrowed(self.0.as_fd())
+            }
+
+            /// Create a new file descriptor which shares the same open file
+            /// description, and so has the same access mode.
+            #[inline]
+            pub fn try_clone(&self) -> io::Result<Self> {
+                self.0.try_clone().map(Self)
+            }
+        }
+
+        impl TryFrom<OwnedFd> for $owned {
+            type Error = RejectedFd<OwnedFd>;
+
+            #[inline]
+            fn try_from(owned: OwnedFd) -> Result<Self, Self::Error> {
+                Self::try_from_filelike(owned)
+            }
+        }
+
+        impl From<$owned> for OwnedFd {
+            #[inline]
+            fn from(owned: $owned) -> Self {
+                owned.0
+            }
+        }
+
+        impl AsFd for $owned {
+            #[inline]
+            fn as_fd(&self) -> BorrowedFd<'_> {
+                self.0.as_fd()
+            }
+        }
+
+        impl AsRawFd for $owned {
+            #[inline]
+            fn as_raw_fd(&self) -> RawFilelike {
+                self.0.as_raw_fd()
+            }
+        }
+
+        impl IntoRawFd for $owned {
+            #[inline]
+            fn into_raw_fd(self) -> RawFilelike {
+                self.0.into_raw_fd()
+            }
+        }
+
+        #[doc = concat!("A borrowed file descriptor which is open for ", $desc, ".")]
+        #[derive(Debug, Clone, Copy)]
+        #[repr(transparent)]
+        pub struct $borrowed<'fd>(BorrowedFd<'fd>);
+
+        impl<'fd> $borrowed<'fd> {
+            #[doc = concat!("Check that `borrowed` is open for ", $desc, ", and wrap it.")]
+            ///
+            /// On failure, the returned error holds `borrowed`.
+            #[inline]
+            pub fn try_from_filelike(
+                borrowed: BorrowedFilelike<'fd>,
+            ) -> Result<Self, RejectedFd<BorrowedFd<'fd>>> {
+                match check(borrowed, $allowed, $reason) {
+                    Ok(()) => Ok(Self(borrowed)),
+                    Err(error) => Err(RejectedFd::new(borrowed, error)),
+                }
+            }
+
+            #[doc = concat!("Wrap `borrowed` without checking that it's open for ", $desc, ".")]
+            ///
+            /// # Safety
+            ///
+            #[doc = concat!("`borrowed` must be open for ", $desc, ".")]
+            #[inline]
+            pub const unsafe fn from_filelike_unchecked(borrowed: BorrowedFilelike<'fd>) -> Self {
+                Self(borrowed)
+            }
+
+            /// Create a new owned file descriptor which shares the same open
+            /// file description, and so has the same access mode.
+            #[inline]
+            pub fn try_clone_to_owned(&self) -> io::Result<$owned> {
+                self.0.try_clone_to_owned().map($owned)
+            }
+        }
+
+        impl<'fd> TryFrom<BorrowedFd<'fd>> for $borrowed<'fd> {
+            type Error = RejectedFd<BorrowedFd<'fd>>;
+
+            #[inline]
+            fn try_from(borrowed: BorrowedFd<'fd>) -> Result<Self, Self::Error> {
+                Self::try_from_filelike(borrowed)
+            }
+        }
+
+        impl<'fd> From<$borrowed<'fd>> for BorrowedFd<'fd> {
+            #[inline]
+            fn from(borrowed: $borrowed<'fd>) -> Self {
+                borrowed.0
+            }
+        }
+
+        impl AsFd for $borrowed<'_> {
+            #[inline]
+            fn as_fd(&self) -> BorrowedFd<'_> {
+                self.0
+            }
+        }
+
+        impl AsRawFd for $borrowed<'_> {
+            #[inline]
+            fn as_raw_fd(&self) -> RawFilelike {
+                self.0.as_raw_fd()
+            }
+        }
+    };
+}
+
+access_fd!(
+    ReadFd,
+    BorrowedReadFd,
+    "reading",
+    AccessMode::is_readable,
+    "file descriptor is not open for reading"
+);
+access_fd!(
+    WriteFd,
+    BorrowedWriteFd,
+    "writing",
+    AccessMode::is_writable,
+    "file descriptor is not open for writing"
+);
+access_fd!(
+    ReadWriteFd,
+    BorrowedReadWriteFd,
+    "reading and writing",
+    |mode| mode == AccessMode::ReadWrite,
+    "file descriptor is not open for reading and writing"
+);
+
+// A file descriptor open for reading and writing can be narrowed to one
+// which is only used for reading or only used for writing.
+
+impl From<ReadWriteFd> for ReadFd {
+    #[inline]
+    fn from(fd: ReadWriteFd) -> Self {
+        Self(fd.0)
+    }
+}
+
+impl From<ReadWriteFd> for WriteFd {
+    #[inline]
+    fn from(fd: ReadWriteFd) -> Self {
+        Self(fd.0)
+    }
+}
+
+impl<'fd> From<BorrowedReadWriteFd<'fd>> for BorrowedReadFd<'fd> {
+    #[inline]
+    fn from(fd: BorrowedReadWriteFd<'fd>) -> Self {
+        Self(fd.0)
+    }
+}
+
+impl<'fd> From<BorrowedReadWriteFd<'fd>> for BorrowedWriteFd<'fd> {
+    #[inline]
+    fn from(fd: BorrowedReadWriteFd<'fd>) -> Self {
+        Self(fd.0)
+    }
+}
@@ -0,0 +1,143 @@
+//! Types for exposing I/O ownership to C.
+//!
+//! The `include/io_lifetimes.h` header, which is also available as
+//! [`HEADER`], defines C typedefs for owned and borrowed file descriptors,
+//! which let C headers express ownership transfer in the same way that Rust
+//! signatures do with [`OwnedFd`] and [`BorrowedFd`]:
+//!
+//! | C type                 | Rust type                                       |
+//! | ---------------------- | ----------------------------------------------- |
+//! | `owned_fd`             | [`OwnedFd`]                                     |
+//! | `borrowed_fd`          | [`BorrowedFd<'_>`]                              |
+//! | `nullable_owned_fd`    | [`NullableOwnedFd`] or `Option<OwnedFd>`        |
+//! | `nullable_borrowed_fd` | [`NullableBorrowedFd`] or `Option<BorrowedFd>`  |
+//!
+//! Each C type is an `int`, and each Rust type has the same layout as a
+//! `c_int`, with `None` represented as `-1`.
+//!
+//! `NullableOwnedFd` and `NullableBorrowedFd` are `#[repr(transparent)]`
+//! wrappers around `Option<OwnedFd>` and `Option<BorrowedFd>` which name the
+//! C types in Rust signatures.
+//!
+//! # Example
+//!
+//! A cdylib exporting a function declared in C as
+//! `nullable_owned_fd my_dup(borrowed_fd fd);`:
+//!
+//! ```rust
+//! use io_lifetimes::c_api::NullableOwnedFd;
+//! use io_lifetimes::BorrowedFd;
+//!
+//! #[no_mangle]
+//! pub extern "C" fn my_dup(fd: BorrowedFd<'_>) -> NullableOwnedFd {
+//!     fd.try_clone_to_owned().ok().into()
+//! }
+//! ```
+//!
+//! [`OwnedFd`]: https://doc.rust-lang.org/stable/std/os/fd/struct.OwnedFd.html
+//! [`BorrowedFd`]: https://doc.rust-lang.org/stable/std/os/fd/struct.BorrowedFd.html
+//! [`BorrowedFd<'_>`]: https://doc.rust-lang.org/stable/std/os/fd/struct.BorrowedFd.html
+
+use crate::{AsFd, BorrowedFd, OwnedFd};
+
+/// The contents of `io_lifetimes.h`, for build scripts which install or
+/// generate C headers.
+pub const HEADER: &str = include_str!("../include/io_lifetimes.h");
+
+/// An owned file descriptor or none, corresponding to the C type
+/// `nullable_owned_fd`.
+///
+/// This has the same layout as a `c_int`, with `-1` for none.
+#[repr(transparent)]
+#[derive(Debug, Default)]
+pub struct NullableOwnedFd(Option<OwnedFd>);
+
+impl NullableOwnedFd {
+    /// A `NullableOwnedFd` which holds no file descriptor.
+    pub const NONE: Self = Self(None);
+
+    /// Return the owned file descriptor, if there is one.
+    #[inline]
+    pub fn into_option(self) -> Option<OwnedFd> {
+        self.0
+    }
+
+    /// Borrow the file descriptor, if there is one.
+    #[inline]
+    pub fn as_borrowed(&self) -> NullableBorrowedFd<'_> {
+        NullableBorrowedFd(self.0.as_ref().map(AsFd::as_fd))
+    }
+
+    /// Test whether this holds no file descriptor.
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+impl From<Option<OwnedFd>> for NullableOwnedFd {
+    #[inline]
+    fn from(owned: Option<OwnedFd>) -> Self {
+        Self(owned)
+    }
+}
+
+impl From<OwnedFd> for NullableOwnedFd {
+    #[inline]
+    fn from(owned: OwnedFd) -> Self {
+        Self(Some(owned))
+    }
+}
+
+impl From<NullableOwnedFd> for Option<OwnedFd> {
+    #[inline]
+    fn from(nullable: NullableOwnedFd) -> Self {
+        nullable.0
+    }
+}
+
+/// A borrowed file descriptor or none, corresponding to the C type
+/// `nullable_borrowed_fd`.
+///
+/// This has the same layout as a `c_int`, with `-1` for none.
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NullableBorrowedFd<'fd>(Option<BorrowedFd<'fd>>);
+
+impl<'fd> NullableBorrowedFd<'fd> {
+    /// A `NullableBorrowedFd` which holds no file descriptor.
+    pub const NONE: Self = Self(None);
+
+    /// Return the borrowed file descriptor, if there is one.
+    #[inline]
+    pub fn into_option(self) -> Option<BorrowedFd<'fd>> {
+        self.0
+    }
+
+    /// Test whether this holds no file descriptor.
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+impl<'fd> From<Option<BorrowedFd<'fd>>> for NullableBorrowedFd<'fd> {
+    #[inline]
+    fn from(borrowed: Option<BorrowedFd<'fd>>) -> Self {
+        Self(borrowed)
+    }
+}
+
+impl<'fd> From<BorrowedFd<'fd>> for NullableBorrowedFd<'fd> {
+    #[inline]
+    fn from(borrowed: BorrowedFd<'fd>) -> Self {
+        Self(Some(borrowed))
+    }
+}
+
+impl<'fd> From<NullableBorrowedFd<'fd>> for Option<BorrowedFd<'fd>> {
+    #[inline]
+    fn from(nullable: NullableBorrowedFd<'fd>) -> Self {
+        nullable.0
+    }
+}
@@ -0,0 +1,413 @@
+//! Channels for sending messages with file descriptors over Unix-domain
+//! sockets.
+//!
+//! [`MessageSender`] and [`MessageReceiver`] send and receive [`Message`]s,
+//! each of which is a byte payload along with a set of file descriptors, over
+//! a [`UnixStream`] or [`UnixDatagram`]. [`FdSender`] and [`FdReceiver`] are a
+//! typed layer on top, which send a value of some type `T`, such as [`File`]
+//! or [`TcpStream`], by transferring its file descriptor.
+//!
+//! # Example
+//!
+//! ```rust
+//! use io_lifetimes::channel::fd_channel;
+//! use std::fs::File;
+//! use std::io::Read;
+//!
+//! let (sender, receiver) = fd_channel::<File>()?;
+//!
+//! sender.send(File::open("Cargo.toml")?)?;
+//!
+//! let mut file = receiver.recv()?.unwrap();
+//! let mut contents = String::new();
+//! file.read_to_string(&mut contents)?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! [`File`]: std::fs::File
+//! [`TcpStream`]: std::net::TcpStream
+
+use crate::scm_rights::{recv_with_fds, send_with_fds};
+use crate::{AsFd, AsFilelike, BorrowedFd, FromFilelike, IntoFilelike, OwnedFd};
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::{UnixDatagram, UnixStream};
+
+/// The size of the header which precedes each message's payload. It holds
+/// the payload length and the number of file descriptors, each as a
+/// native-endian `u32`.
+const HEADER_LEN: usize = 8;
+
+/// The maximum number of file descriptors in a message. This is Linux's
+/// `SCM_MAX_FD`.
+pub const MAX_FDS: usize = 253;
+
+/// The maximum length of a message's payload.
+///
+/// Received headers claiming a longer payload are rejected before any buffer
+/// is allocated for it, so a peer can't make us allocate arbitrary amounts of
+/// memory.
+pub const MAX_MESSAGE_LEN: usize = 64 * 1024 * 1024;
+
+/// A message, consisting of a byte payload and a set of file descriptors.
+#[derive(Debug, Default)]
+pub struct Message {
+    /// The payload.
+    pub data: Vec<u8>,
+
+    /// The file descriptors.
+    pub fds: Vec<OwnedFd>,
+}
+
+/// The socket underlying a [`MessageSender`] or [`MessageReceiver`].
+#[derive(Debug)]
+enum Socket {
+    Stream(UnixStream),
+    Datagram(UnixDatagram),
+}
+
+impl Socket {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        match self {
+            Self::Stream(stream) => stream.as_fd(),
+            Self::Datagram(datagram) => datagram.as_fd(),
+        }
+    }
+}
+
+/// The sending half of a message channel.
+#[derive(Debug)]
+pub struct MessageSender {
+    socket: Socket,
+}
+
+/// The receiving half of a message channel.
+#[derive(Debug)]
+pub struct MessageReceiver {
+    socket: Socket,
+}
+
+/// Create a connected [`MessageSender`] and [`MessageReceiver`] over a
+/// [`UnixStream`] pair.
+#[inline]
+pub fn message_channel() -> io::Result<(MessageSender, MessageReceiver)> {
+    let (a, b) = UnixStream::pair()?;
+    Ok((a.into(), b.into()))
+}
+
+impl MessageSender {
+    /// Send a message with payload `data` and file descriptors `fds`.
+    ///
+    /// `data` may be at most [`MAX_MESSAGE_LEN`] bytes long, and `fds` may
+    /// hold at most [`MAX_FDS`] file descriptors.
+    ///
+    /// The file descriptors are duplicated into the receiver; they remain
+    /// open in this process.
+    pub fn send(&self, data: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<()> {
+        let header = encode_header(data.len(), fds.len())?;
+        let mut buf = Vec::with_capacity(HEADER_LEN + data.len());
+        buf.extend_from_slice(&header);
+        buf.extend_from_slice(data);
+
+        match &self.socket {
+            Socket::Stream(stream) => {
+                // The file descriptors are sent with the first byte, so if
+                // `sendmsg` writes only part of the message, write the rest
+                // normally.
+                let n = send_with_fds(stream, &buf, fds)?;
+                let mut stream = stream;
+                stream.write_all(&buf[n..])
+            }
+            Socket::Datagram(datagram) => {
+                let n = send_with_fds(datagram, &buf, fds)?;
+                if n != buf.len() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to send whole datagram",
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl MessageReceiver {
+    /// Receive a message.
+    ///
+    /// Returns `Ok(None)` if the channel is a stream and the sender has been
+    /// closed. If the message is malformed, returns an error of kind
+    /// [`io::ErrorKind::InvalidData`] and closes any file descriptors that
+    /// were received with it.
+    pub fn recv(&self) -> io::Result<Option<Message>> {
+        match &self.socket {
+            Socket::Stream(stream) => recv_stream(stream),
+            Socket::Datagram(datagram) => recv_datagram(datagram).map(Some),
+        }
+    }
+}
+
+fn recv_stream(stream: &UnixStream) -> io::Result<Option<Message>> {
+    let mut header = [0_u8; HEADER_LEN];
+    let received = recv_with_fds(stream, &mut header, MAX_FDS)?;
+    if received.len == 0 {
+        return Ok(None);
+    }
+    if received.fds_truncated {
+        return Err(invalid_data("too many file descriptors"));
+    }
+
+    let mut stream = stream;
+    stream.read_exact(&mut header[received.len..])?;
+    let (len, num_fds) = decode_header(&header);
+    if len > MAX_MESSAGE_LEN {
+        return Err(invalid_data("message too long"));
+    }
+    if received.fds.len() != num_fds {
+        return Err(invalid_data("wrong number of file descriptors"));
+    }
+
+    let mut data = vec![0_u8; len];
+    stream.read_exact(&mut data)?;
+
+    Ok(Some(Message {
+        data,
+        fds: received.fds,
+    }))
+}
+
+fn recv_datagram(datagram: &UnixDatagram) -> io::Result<Message> {
+    // Peek at the header to find out how big the message is. This doesn't
+    // receive any file descriptors.
+    let mut header = [0_u8; HEADER_LEN];
+    // Safety: `header` is a valid buffer for `recv` to write to.
+    let n = unsafe {
+        libc::recv(
+            datagram.as_raw_fd(),
+            header.as_mut_ptr().cast(),
+            header.len(),
+            libc::MSG_PEEK,
+        )
+    };
+    if n == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let (len, num_fds) = if n as usize == HEADER_LEN {
+        decode_header(&header)
+    } else {
+        (0, 0)
+    };
+    if len > MAX_MESSAGE_LEN || num_fds > MAX_FDS {
+        // Receive the message and discard it, so that it doesn't stay at the
+        // front of the queue. Any file descriptors with it are closed.
+        recv_with_fds(datagram, &mut header, 0)?;
+        return Err(invalid_data(if len > MAX_MESSAGE_LEN {
+            "message too long"
+        } else {
+            "too many file descriptors"
+        }));
+    }
+
+    let mut buf = vec![0_u8; HEADER_LEN + len];
+    let received = recv_with_fds(datagram, &mut buf, num_fds)?;
+    if received.len != buf.len() || received.data_truncated {
+        return Err(invalid_data("malformed message"));
+    }
+    if received.fds_truncated || received.fds.len() != num_fds {
+        return Err(invalid_data("wrong number of file descriptors"));
+    }
+
+    buf.drain(..HEADER_LEN);
+    Ok(Message {
+        data: buf,
+        fds: received.fds,
+    })
+}
+
+fn encode_header(len: usize, num_fds: usize) -> io::Result<[u8; HEADER_LEN]> {
+    if len > MAX_MESSAGE_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "message too long",
+        ));
+    }
+    if num_fds > MAX_FDS {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "too many file descriptors",
+        ));
+    }
+
+    let mut header = [0_u8; HEADER_LEN];
+    header[..4].copy_from_slice(&(len as u32).to_ne_bytes());
+    header[4..].copy_from_slice(&(num_fds as u32).to_ne_bytes());
+    Ok(header)
+}
+
+fn decode_header(header: &[u8; HEADER_LEN]) -> (usize, usize) {
+    let len = u32::from_ne_bytes([header[0], header[1], header[2], header[3]]);
+    let num_fds = u32::from_ne_bytes([header[4], header[5], header[6], header[7]]);
+    (len as usize, num_fds as usize)
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+impl From<UnixStream> for MessageSender {
+    #[inline]
+    fn from(stream: UnixStream) -> Self {
+        Self {
+            socket: Socket::Stream(stream),
+        }
+    }
+}
+
+impl From<UnixDatagram> for MessageSender {
+    /// Construct a `MessageSender` from a `UnixDatagram`, which must be
+    /// connected.
+    #[inline]
+    fn from(datagram: UnixDatagram) -> Self {
+        Self {
+            socket: Socket::Datagram(datagram),
+        }
+    }
+}
+
+impl From<UnixStream> for MessageReceiver {
+    #[inline]
+    fn from(stream: UnixStream) -> Self {
+        Self {
+            socket: Socket::Stream(stream),
+        }
+    }
+}
+
+impl From<UnixDatagram> for MessageReceiver {
+    #[inline]
+    fn from(datagram: UnixDatagram) -> Self {
+        Self {
+            socket: Socket::Datagram(datagram),
+        }
+    }
+}
+
+impl AsFd for MessageSender {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.socket.as_fd()
+    }
+}
+
+impl AsFd for MessageReceiver {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.socket.as_fd()
+    }
+}
+
+/// The sending half of a typed channel, which sends values of type `T` by
+/// transferring their file descriptors.
+pub struct FdSender<T> {
+    inner: MessageSender,
+    _phantom: PhantomData<fn(T)>,
+}
+
+/// The receiving half of a typed channel, which receives values of type `T`
+/// by reconstructing them from their file descriptors.
+pub struct FdReceiver<T> {
+    inner: MessageReceiver,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+/// Create a connected [`FdSender`] and [`FdReceiver`] over a [`UnixStream`]
+/// pair.
+#[inline]
+pub fn fd_channel<T: IntoFilelike + FromFilelike>() -> io::Result<(FdSender<T>, FdReceiver<T>)> {
+    let (sender, receiver) = message_channel()?;
+    Ok((sender.into(), receiver.into()))
+}
+
+impl<T: IntoFilelike> FdSender<T> {
+    /// Send `value`. Its file descriptor is closed in this process once it's
+    /// been sent.
+    #[inline]
+    pub fn send(&self, value: T) -> io::Result<()> {
+        let owned = value.into_filelike();
+        self.inner.send(&[], &[owned.as_filelike()])
+    }
+}
+
+impl<T: FromFilelike> FdReceiver<T> {
+    /// Receive a value.
+    ///
+    /// Returns `Ok(None)` if the channel is a stream and the sender has been
+    /// closed.
+    #[inline]
+    pub fn recv(&self) -> io::Result<Option<T>> {
+        let message = match self.inner.recv()? {
+            Some(message) => message,
+            None => return Ok(None),
+        };
+        let mut fds = message.fds.into_iter();
+        match (fds.next(), fds.next()) {
+            (Some(owned), None) => Ok(Some(T::from_filelike(owned))),
+            _ => Err(invalid_data("expected exactly one file descriptor")),
+        }
+    }
+}
+
+impl<T> From<MessageSender> for FdSender<T> {
+    #[inline]
+    fn from(inner: MessageSender) -> Self {
+        Self {
+            inner,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> From<MessageReceiver> for FdReceiver<T> {
+    #[inline]
+    fn from(inner: MessageReceiver) -> Self {
+        Self {
+            inner,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> AsFd for FdSender<T> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+impl<T> AsFd for FdReceiver<T> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+impl<T> fmt::Debug for FdSender<T> {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FdSender")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<T> fmt::Debug for FdReceiver<T> {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FdReceiver")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
@@ -0,0 +1,84 @@
+//! Closing with error reporting.
+
+use crate::IntoFilelike;
+use std::io;
+
+/// An extension trait for closing an owned filelike object and observing
+/// whether the close succeeded.
+///
+/// Dropping an [`OwnedFd`] or [`OwnedHandle`] closes it and ignores any
+/// error. Some filesystems, such as NFS and FUSE filesystems, report write
+/// errors such as `EIO` or `ENOSPC` when a file is closed, so code which
+/// needs to know whether its writes were durable can use [`close`] instead.
+///
+/// # `EINTR`
+///
+/// If the close is interrupted by a signal, this returns an error of kind
+/// [`io::ErrorKind::Interrupted`]. The close is never retried. On Linux, the
+/// file descriptor is always released, even when an error is returned, so
+/// retrying could close an unrelated file descriptor allocated in the
+/// meantime by another thread. POSIX leaves the state of the file
+/// descriptor unspecified after `EINTR`; on platforms where it remains open,
+/// it's leaked rather than risk closing the wrong file descriptor.
+///
+/// [`OwnedFd`]: crate::OwnedFd
+/// [`OwnedHandle`]: https://doc.rust-lang.org/stable/std/os/windows/io/struct.OwnedHandle.html
+/// [`close`]: CloseChecked::close
+pub trait CloseChecked: IntoFilelike {
+    /// Close the underlying filelike object, returning any error reported by
+    /// the OS.
+    ///
+    /// In either case, `self` is consumed and the resource is no longer owned.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use io_lifetimes::CloseChecked;
+    /// use std::fs::File;
+    /// use std::io::Write;
+    ///
+    /// let mut file = File::create("output.txt")?;
+    /// file.write_all(b"hello")?;
+    /// file.close()?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    fn close(self) -> io::Result<()>;
+}
+
+#[cfg(any(unix, target_os = "wasi"))]
+impl<T: IntoFilelike> CloseChecked for T {
+    #[inline]
+    fn close(self) -> io::Result<()> {
+        use crate::raw::IntoRawFilelike;
+
+        let raw = self.into_filelike().into_raw_filelike();
+
+        // Safety: We own the file descriptor, and `into_raw_filelike`
+        // relinquishes that ownership, so nothing else will close it.
+        let result = match unsafe { libc::close(raw) } {
+            -1 => Err(io::Error::last_os_error()),
+            _ => Ok(()),
+        };
+
+        #[cfg(all(unix, feature = "track-ownership"))]
+        crate::track::close(raw, &result);
+
+        result
+    }
+}
+
+#[cfg(windows)]
+impl<T: IntoFilelike> CloseChecked for T {
+    #[inline]
+    fn close(self) -> io::Result<()> {
+        use crate::raw::IntoRawFilelike;
+        use windows_sys::Win32::Foundation::CloseHandle;
+
+        // Safety: We own the handle, and `into_raw_filelike` relinquishes
+        // that ownership, so nothing else will close it.
+        match unsafe { CloseHandle(self.into_filelike().into_raw_filelike() as _) } {
+            0 => Err(io::Error::last_os_error()),
+            _ => Ok(()),
+        }
+    }
+}
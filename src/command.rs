@@ -0,0 +1,194 @@
+//! Passing file descriptors to child processes at specific numbers.
+//!
+//! [`CommandFdExt::map_fd`] arranges for a borrowed file descriptor to be
+//! duplicated into a child process spawned with [`Command`] at a given file
+//! descriptor number, without any `unsafe` code or raw `dup2` calls in the
+//! caller. The returned [`MappedCommand`] takes ownership of the `Command`
+//! and borrows the source file descriptors, so they stay open until the child
+//! has been spawned.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use io_lifetimes::command::CommandFdExt;
+//! use std::fs::File;
+//! use std::process::Command;
+//!
+//! let input = File::open("Cargo.toml")?;
+//! let mut command = Command::new("/bin/sh");
+//! command.args(["-c", "cat <&3"]);
+//!
+//! // Whatever number `input` has here, the child sees it as file
+//! // descriptor 3.
+//! let status = command.map_fd(&input, 3).status()?;
+//! assert!(status.success());
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::raw::RawFilelike;
+use crate::{AsFilelike, BorrowedFilelike};
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, ExitStatus};
+
+/// An extension trait for [`Command`] for passing file descriptors to the
+/// child at specific numbers.
+pub trait CommandFdExt {
+    /// Arrange for `source` to be duplicated into the child as file
+    /// descriptor number `child_fd`.
+    ///
+    /// The returned [`MappedCommand`] takes ownership of `self` and borrows
+    /// `source` until the child is spawned, and more mappings can be added
+    /// with [`MappedCommand::map_fd`]. Spawning consumes it, so the mappings,
+    /// which are only valid while their sources are borrowed, can't be
+    /// applied to a later spawn.
+    fn map_fd<'a, Filelike: AsFilelike + ?Sized>(
+        self,
+        source: &'a Filelike,
+        child_fd: RawFilelike,
+    ) -> MappedCommand<'a>;
+}
+
+impl CommandFdExt for Command {
+    #[inline]
+    fn map_fd<'a, Filelike: AsFilelike + ?Sized>(
+        self,
+        source: &'a Filelike,
+        child_fd: RawFilelike,
+    ) -> MappedCommand<'a> {
+        MappedCommand {
+            command: self,
+            mappings: Vec::new(),
+        }
+        .map_fd(source, child_fd)
+    }
+}
+
+/// A [`Command`] with file descriptors to pass to the child at specific
+/// numbers, returned by [`CommandFdExt::map_fd`].
+///
+/// It owns the `Command`, which is consumed when the child is spawned, and
+/// which can still be configured with [`MappedCommand::command_mut`].
+///
+/// In the child, after the standard I/O streams are set up and before the
+/// program is executed, each source is duplicated at its target number,
+/// clearing the close-on-exec flag on the target. Sources are first
+/// duplicated above all of the targets, so mappings can target numbers
+/// which are in use by other sources, including swapping two file
+/// descriptors. File descriptors which aren't mapping targets are left as
+/// they are, so sources which are close-on-exec aren't inherited at their
+/// original numbers.
+///
+/// If several mappings have the same target, the last one takes effect.
+#[must_use]
+pub struct MappedCommand<'a> {
+    command: Command,
+    mappings: Vec<(BorrowedFilelike<'a>, RawFilelike)>,
+}
+
+impl<'a> MappedCommand<'a> {
+    /// Arrange for `source` to be duplicated into the child as file
+    /// descriptor number `child_fd`, in addition to the existing mappings.
+    #[inline]
+    pub fn map_fd<Filelike: AsFilelike + ?Sized>(
+        mut self,
+        source: &'a Filelike,
+        child_fd: RawFilelike,
+    ) -> Self {
+        self.mappings.push((source.as_filelike(), child_fd));
+        self
+    }
+
+    /// Return a reference to the `Command`.
+    #[inline]
+    pub fn command(&self) -> &Command {
+        &self.command
+    }
+
+    /// Return a mutable reference to the `Command`, to configure it further,
+    /// such as with [`Command::env`] or [`Command::stdout`].
+    #[inline]
+    pub fn command_mut(&mut self) -> &mut Command {
+        &mut self.command
+    }
+
+    /// Spawn the child process with the file descriptor mappings, as with
+    /// [`Command::spawn`].
+    ///
+    /// Returns an [`io::ErrorKind::InvalidInput`] error if any target is
+    /// negative.
+    pub fn spawn(self) -> io::Result<Child> {
+        let mut targets: Vec<(RawFilelike, RawFilelike)> = Vec::new();
+        for (source, child_fd) in &self.mappings {
+            if *child_fd < 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "negative child file descriptor number",
+                ));
+            }
+            targets.retain(|(_, target)| target != child_fd);
+            targets.push((source.as_raw_fd(), *child_fd));
+        }
+
+        // Allocate the temporaries' storage in the parent, since the child
+        // may not allocate memory between `fork` and `exec`.
+        let mut temps: Vec<RawFilelike> = Vec::with_capacity(targets.len());
+
+        // Safety: The callback only calls async-signal-safe functions, and
+        // doesn't allocate, since `temps` has enough capacity for all of the
+        // targets. The raw sources remain open while the callback can run,
+        // since they're borrowed by `self`, and the callback is dropped
+        // along with the `Command` when this returns.
+        let mut command = self.command;
+        unsafe {
+            command.pre_exec(move || remap(&targets, &mut temps));
+        }
+
+        command.spawn()
+    }
+
+    /// Spawn the child process with the file descriptor mappings and wait
+    /// for it to exit, as with [`Command::status`].
+    #[inline]
+    pub fn status(self) -> io::Result<ExitStatus> {
+        self.spawn()?.wait()
+    }
+}
+
+/// Duplicate each `(source, target)` pair's source at its target, in a
+/// child process between `fork` and `exec`.
+fn remap(targets: &[(RawFilelike, RawFilelike)], temps: &mut Vec<RawFilelike>) -> io::Result<()> {
+    let Some(max_target) = targets.iter().map(|(_, target)| *target).max() else {
+        return Ok(());
+    };
+
+    // First, move every source above all of the targets, so that
+    // duplicating at one target can't clobber a source which is yet to be
+    // duplicated. The temporaries are close-on-exec, and on failure the
+    // child exits without executing the program.
+    temps.clear();
+    for (source, _) in targets {
+        let temp = unsafe { libc::fcntl(*source, libc::F_DUPFD_CLOEXEC, max_target + 1) };
+        if temp == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        temps.push(temp);
+    }
+
+    // Then duplicate each temporary at its target. `dup2` clears the
+    // close-on-exec flag on the new file descriptor.
+    for (temp, (_, target)) in temps.iter().zip(targets) {
+        if unsafe { libc::dup2(*temp, *target) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    for temp in temps.iter() {
+        unsafe {
+            libc::close(*temp);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,200 @@
+//! Human-readable descriptions of file descriptors.
+//!
+//! [`DescribeFd`] wraps a reference to a filelike object, and its `Debug` and
+//! `Display` impls query the OS to describe what the file descriptor refers
+//! to, which is more useful in logs than just the number.
+//!
+//! # Example
+//!
+//! ```rust
+//! use io_lifetimes::describe::DescribeFd;
+//! use std::fs::File;
+//!
+//! let file = File::open("Cargo.toml")?;
+//! println!("{}", DescribeFd(&file));
+//! // fd 3: regular file /path/to/Cargo.toml, read-only, cloexec
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! When the `describe` feature is enabled, the `Debug` impls of the views in
+//! [`views`] include a `DescribeFd` of the resource being viewed.
+//!
+//! [`views`]: crate::views
+
+use crate::kind::{classify, Kind};
+use crate::open_fds::AccessMode;
+use crate::raw::RawFilelike;
+use crate::views::{SocketDomain, SocketType};
+use crate::{AsFilelike, AsSocketlike, BorrowedFd};
+use std::fmt;
+use std::io;
+use std::net::TcpStream;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+
+/// A wrapper around a reference to a filelike object, whose `Debug` and
+/// `Display` impls describe the file descriptor.
+///
+/// The description includes the file descriptor's number, the type of
+/// resource it refers to, its path or its socket addresses, its access mode,
+/// and whether it's non-blocking and close-on-exec. Each of these is queried
+/// from the OS when the wrapper is formatted.
+#[derive(Clone, Copy)]
+pub struct DescribeFd<'a, T>(pub &'a T);
+
+/// The information we display about a file descriptor.
+struct Info {
+    raw: RawFilelike,
+    kind: &'static str,
+    path: Option<PathBuf>,
+    local: Option<String>,
+    peer: Option<String>,
+    access: Option<AccessMode>,
+    nonblocking: bool,
+    cloexec: bool,
+}
+
+impl Info {
+    fn new(fd: BorrowedFd<'_>) -> io::Result<Self> {
+        let raw = fd.as_raw_fd();
+
+        // Safety: `fd` is a valid file descriptor, and `F_GETFD` and
+        // `F_GETFL` take no pointer arguments.
+        let (fd_flags, fl_flags) = unsafe {
+            (
+                libc::fcntl(raw, libc::F_GETFD),
+                libc::fcntl(raw, libc::F_GETFL),
+            )
+        };
+        if fd_flags == -1 || fl_flags == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut info = Self {
+            raw,
+            kind: "unknown",
+            path: None,
+            local: None,
+            peer: None,
+            access: AccessMode::from_flags(fl_flags),
+            nonblocking: fl_flags & libc::O_NONBLOCK != 0,
+            cloexec: fd_flags & libc::FD_CLOEXEC != 0,
+        };
+
+        let kind = classify(fd)?;
+        info.kind = match kind {
+            Kind::RegularFile => "regular file",
+            Kind::Directory => "directory",
+            Kind::Pipe => "pipe",
+            Kind::CharacterDevice => "character device",
+            Kind::Tty => "tty",
+            Kind::BlockDevice => "block device",
+            Kind::Socket(Some(SocketType::Stream)) => "stream socket",
+            Kind::Socket(Some(SocketType::Datagram)) => "datagram socket",
+            Kind::Socket(Some(SocketType::SeqPacket)) => "sequenced-packet socket",
+            Kind::Socket(Some(SocketType::Raw)) => "raw socket",
+            Kind::Socket(None) => "socket",
+            Kind::Symlink => "symlink",
+            Kind::Unknown => "unknown",
+        };
+
+        if kind.is_socket() {
+            match crate::inspect::socket_domain(fd)? {
+                Some(SocketDomain::Inet) => {
+                    // `TcpStream`'s address functions work on any IP socket.
+                    let view = fd.as_socketlike_view::<TcpStream>();
+                    info.local = view.local_addr().ok().map(|addr| addr.to_string());
+                    info.peer = view.peer_addr().ok().map(|addr| addr.to_string());
+                }
+                Some(SocketDomain::Unix) => {
+                    // `UnixStream`'s address functions work on any
+                    // Unix-domain socket.
+                    let view = fd.as_socketlike_view::<UnixStream>();
+                    info.local = view.local_addr().ok().map(|addr| format!("{:?}", addr));
+                    info.peer = view.peer_addr().ok().map(|addr| format!("{:?}", addr));
+                }
+                None => {}
+            }
+            return Ok(info);
+        }
+
+        info.path = std::fs::read_link(format!("/proc/self/fd/{}", raw)).ok();
+        Ok(info)
+    }
+}
+
+fn access_str(access: AccessMode) -> &'static str {
+    match access {
+        AccessMode::ReadOnly => "read-only",
+        AccessMode::WriteOnly => "write-only",
+        AccessMode::ReadWrite => "read-write",
+    }
+}
+
+impl<T: AsFilelike> fmt::Debug for DescribeFd<'_, T> {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fd = self.0.as_filelike();
+        let info = match Info::new(fd) {
+            Ok(info) => info,
+            Err(err) => {
+                return f
+                    .debug_struct("DescribeFd")
+                    .field("fd", &fd.as_raw_fd())
+                    .field("error", &err)
+                    .finish()
+            }
+        };
+
+        let mut b = f.debug_struct("DescribeFd");
+        b.field("fd", &info.raw).field("type", &info.kind);
+        if let Some(path) = &info.path {
+            b.field("path", path);
+        }
+        if let Some(local) = &info.local {
+            b.field("local", local);
+        }
+        if let Some(peer) = &info.peer {
+            b.field("peer", peer);
+        }
+        if let Some(access) = info.access {
+            b.field("access", &access);
+        }
+        b.field("nonblocking", &info.nonblocking)
+            .field("cloexec", &info.cloexec)
+            .finish()
+    }
+}
+
+impl<T: AsFilelike> fmt::Display for DescribeFd<'_, T> {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fd = self.0.as_filelike();
+        let info = match Info::new(fd) {
+            Ok(info) => info,
+            Err(err) => return write!(f, "fd {}: {}", fd.as_raw_fd(), err),
+        };
+
+        write!(f, "fd {}: {}", info.raw, info.kind)?;
+        if let Some(path) = &info.path {
+            write!(f, " {}", path.display())?;
+        }
+        match (&info.local, &info.peer) {
+            (Some(local), Some(peer)) => write!(f, " {} -> {}", local, peer)?,
+            (Some(local), None) => write!(f, " {}", local)?,
+            (None, Some(peer)) => write!(f, " -> {}", peer)?,
+            (None, None) => {}
+        }
+        if let Some(access) = info.access {
+            write!(f, ", {}", access_str(access))?;
+        }
+        if info.nonblocking {
+            write!(f, ", nonblocking")?;
+        }
+        if info.cloexec {
+            write!(f, ", cloexec")?;
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,23 @@
-//! This is just a sample of what FFI using this crate can look like.
+//! I/O-safe declarations of FFI functions.
+//!
+//! These are declarations of libc and Windows functions that take and return
+//! file descriptors and handles, using [`OwnedFd`] and [`BorrowedFd`] (and
+//! their Windows counterparts) in place of raw values. Functions which return
+//! `-1` on failure are declared to return `Option<OwnedFd>`, which the niche
+//! optimization gives the same ABI as a raw `c_int`, with `None` for `-1`.
+//! Functions which write file descriptors into an array are declared to take
+//! a pointer to `[OwnedFd; 2]`, which should point to uninitialized memory,
+//! such as from a [`MaybeUninit`], and is initialized when they succeed.
+//!
+//! Functions such as `read` still report errors with `-1` and `errno`, and
+//! the functions are still `unsafe` to call, because of their pointer
+//! arguments and because they can't check their file descriptor arguments'
+//! types. But the signatures ensure that file descriptors are only passed to
+//! them while borrowed or owned, and that returned file descriptors are owned.
+//!
+//! [`OwnedFd`]: https://doc.rust-lang.org/stable/std/os/fd/struct.OwnedFd.html
+//! [`BorrowedFd`]: https://doc.rust-lang.org/stable/std/os/fd/struct.BorrowedFd.html
+//! [`MaybeUninit`]: std::mem::MaybeUninit
 
 #![allow(missing_docs)]
 
@@ -7,6 +26,8 @@ use crate::{BorrowedFd, OwnedFd};
 #[cfg(windows)]
 use crate::{BorrowedHandle, HandleOrInvalid};
 
+#[cfg(unix)]
+use libc::c_uint;
 #[cfg(any(unix, target_os = "wasi", target_os = "hermit"))]
 use libc::{c_char, c_int, c_void, size_t, ssize_t};
 #[cfg(windows)]
@@ -34,9 +55,95 @@ extern "C" {
 #[cfg(any(unix, target_os = "wasi"))]
 pub use libc::{O_CLOEXEC, O_CREAT, O_RDONLY, O_RDWR, O_TRUNC, O_WRONLY};
 
-// The Windows analogs of the above. Note the use of [`HandleOrInvalid`] as
-// the return type for `CreateFileW`, since that function is defined to return
-// [`INVALID_HANDLE_VALUE`] on error instead of null.
+// Functions for opening, duplicating, and inspecting file descriptors.
+//
+// `openat`'s `dirfd` may be `AT_FDCWD`, which, like `rustix::fs::CWD`, can
+// be passed as a `BorrowedFd` created with `BorrowedFd::borrow_raw`.
+#[cfg(unix)]
+extern "C" {
+    pub fn openat(
+        dirfd: BorrowedFd<'_>,
+        pathname: *const c_char,
+        flags: c_int,
+        ...
+    ) -> Option<OwnedFd>;
+    pub fn dup(fd: BorrowedFd<'_>) -> Option<OwnedFd>;
+    pub fn pipe(fds: *mut [OwnedFd; 2]) -> c_int;
+    pub fn socket(domain: c_int, ty: c_int, protocol: c_int) -> Option<OwnedFd>;
+    pub fn socketpair(domain: c_int, ty: c_int, protocol: c_int, sv: *mut [OwnedFd; 2]) -> c_int;
+    pub fn fcntl(fd: BorrowedFd<'_>, cmd: c_int, ...) -> c_int;
+    #[cfg_attr(
+        all(target_os = "macos", not(target_arch = "aarch64")),
+        link_name = "fstat$INODE64"
+    )]
+    pub fn fstat(fd: BorrowedFd<'_>, buf: *mut libc::stat) -> c_int;
+}
+#[cfg(unix)]
+pub use libc::{
+    AF_INET, AF_UNIX, AT_FDCWD, FD_CLOEXEC, F_DUPFD_CLOEXEC, F_GETFD, F_GETFL, F_SETFD, F_SETFL,
+    O_DIRECTORY, O_NONBLOCK, SOCK_DGRAM, SOCK_STREAM,
+};
+
+/// `fcntl` with `F_DUPFD_CLOEXEC` returns a new file descriptor, which the
+/// `c_int` return type of `fcntl` can't express, so this wraps it to return
+/// an `Option<OwnedFd>`.
+#[cfg(unix)]
+#[inline]
+pub fn fcntl_dupfd_cloexec(fd: BorrowedFd<'_>, min: c_int) -> Option<OwnedFd> {
+    use std::os::unix::io::FromRawFd;
+
+    // Safety: `F_DUPFD_CLOEXEC` takes an integer argument.
+    match unsafe { fcntl(fd, F_DUPFD_CLOEXEC, min) } {
+        -1 => None,
+        // Safety: `F_DUPFD_CLOEXEC` succeeded, so we own the new file
+        // descriptor.
+        raw => Some(unsafe { OwnedFd::from_raw_fd(raw) }),
+    }
+}
+
+// Linux-specific functions. `dup3`'s `newfd` is a raw value, since it may or
+// may not be open; if it is, `dup3` closes it, so the caller must own it and
+// relinquish ownership of it, such as with `IntoRawFd`.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+extern "C" {
+    pub fn dup3(oldfd: BorrowedFd<'_>, newfd: c_int, flags: c_int) -> Option<OwnedFd>;
+    pub fn pipe2(fds: *mut [OwnedFd; 2], flags: c_int) -> c_int;
+    pub fn accept4(
+        sockfd: BorrowedFd<'_>,
+        addr: *mut libc::sockaddr,
+        addrlen: *mut libc::socklen_t,
+        flags: c_int,
+    ) -> Option<OwnedFd>;
+    pub fn ioctl(fd: BorrowedFd<'_>, request: libc::Ioctl, ...) -> c_int;
+    pub fn memfd_create(name: *const c_char, flags: c_uint) -> Option<OwnedFd>;
+    pub fn eventfd(initval: c_uint, flags: c_int) -> Option<OwnedFd>;
+}
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub use libc::{
+    EFD_CLOEXEC, EFD_NONBLOCK, FIOCLEX, FIONBIO, FIONCLEX, FIONREAD, MFD_CLOEXEC, SOCK_CLOEXEC,
+    SOCK_NONBLOCK,
+};
+
+/// `close_range` closes every file descriptor in `first..=last`, so the
+/// caller must own all of them, and they're passed as raw values. Older
+/// libcs, including glibc before 2.34, don't have a wrapper for it, so this
+/// calls the syscall directly, with the same wrapper that [`OwnedFdSet`] uses.
+///
+/// [`OwnedFdSet`]: crate::fd_set::OwnedFdSet
+///
+/// # Safety
+///
+/// The caller must own all of the file descriptors in `first..=last`.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[inline]
+pub unsafe fn close_range(first: c_uint, last: c_uint, flags: c_uint) -> c_int {
+    crate::fd_set::close_range(first, last, flags)
+}
+
+// The Windows analogs of `open`, `read`, and `write`. Note the use of
+// [`HandleOrInvalid`] as the return type for `CreateFileW`, since that
+// function is defined to return [`INVALID_HANDLE_VALUE`] on error instead of
+// null.
 #[cfg(windows)]
 extern "system" {
     pub fn CreateFileW(
@@ -0,0 +1,319 @@
+//! An ordered collection of owned file descriptors.
+//!
+//! [`OwnedFdSet`] holds a set of [`OwnedFd`]s ordered by their raw file
+//! descriptor numbers. When it's dropped, it closes contiguous runs of file
+//! descriptors with a single `close_range` call on platforms which support
+//! it, rather than closing each one individually.
+//!
+//! # Example
+//!
+//! ```rust
+//! use io_lifetimes::fd_set::OwnedFdSet;
+//! use io_lifetimes::OwnedFd;
+//! use std::fs::File;
+//!
+//! let mut set = OwnedFdSet::new();
+//! for _ in 0..4 {
+//!     set.insert(OwnedFd::from(File::open("Cargo.toml")?));
+//! }
+//! assert_eq!(set.len(), 4);
+//!
+//! // Close them all.
+//! drop(set);
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::raw::RawFilelike;
+use crate::{BorrowedFd, OwnedFd};
+use std::collections::btree_map::{self, BTreeMap};
+use std::fmt;
+use std::iter::FusedIterator;
+use std::mem::take;
+use std::os::unix::io::{AsFd, AsRawFd, IntoRawFd};
+
+/// An ordered set of owned file descriptors, which closes them in bulk when
+/// dropped.
+#[derive(Default)]
+pub struct OwnedFdSet {
+    fds: BTreeMap<RawFilelike, OwnedFd>,
+}
+
+impl OwnedFdSet {
+    /// Create an empty set.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            fds: BTreeMap::new(),
+        }
+    }
+
+    /// Add `fd` to the set.
+    #[inline]
+    pub fn insert(&mut self, fd: OwnedFd) {
+        let prev = self.fds.insert(fd.as_raw_fd(), fd);
+
+        // Two `OwnedFd`s can't own the same file descriptor.
+        debug_assert!(prev.is_none());
+    }
+
+    /// Remove the file descriptor with the number `raw` from the set and
+    /// return it, if it's present.
+    #[inline]
+    pub fn remove(&mut self, raw: RawFilelike) -> Option<OwnedFd> {
+        self.fds.remove(&raw)
+    }
+
+    /// Remove the lowest-numbered file descriptor from the set and return
+    /// it, if the set isn't empty.
+    #[inline]
+    pub fn pop_first(&mut self) -> Option<OwnedFd> {
+        self.fds.pop_first().map(|(_raw, fd)| fd)
+    }
+
+    /// Remove the highest-numbered file descriptor from the set and return
+    /// it, if the set isn't empty.
+    #[inline]
+    pub fn pop_last(&mut self) -> Option<OwnedFd> {
+        self.fds.pop_last().map(|(_raw, fd)| fd)
+    }
+
+    /// Borrow the file descriptor with the number `raw`, if it's present.
+    #[inline]
+    pub fn get(&self, raw: RawFilelike) -> Option<BorrowedFd<'_>> {
+        self.fds.get(&raw).map(AsFd::as_fd)
+    }
+
+    /// Test whether the file descriptor with the number `raw` is present.
+    #[inline]
+    pub fn contains(&self, raw: RawFilelike) -> bool {
+        self.fds.contains_key(&raw)
+    }
+
+    /// Return the number of file descriptors in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.fds.len()
+    }
+
+    /// Test whether the set is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.fds.is_empty()
+    }
+
+    /// Iterate over the file descriptors in the set in ascending order,
+    /// borrowing them.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.fds.values(),
+        }
+    }
+}
+
+impl Drop for OwnedFdSet {
+    #[inline]
+    fn drop(&mut self) {
+        let mut fds = take(&mut self.fds)
+            .into_values()
+            .map(IntoRawFd::into_raw_fd)
+            .peekable();
+
+        // Find each contiguous run of file descriptors and close it.
+        while let Some(first) = fds.next() {
+            let mut last = first;
+            while fds.peek() == Some(&(last + 1)) {
+                last += 1;
+                fds.next();
+            }
+
+            // Safety: We owned the file descriptors in `first..=last`, and
+            // relinquished ownership with `into_raw_fd` so that we can close
+            // them here.
+            unsafe { close_run(first, last) }
+        }
+    }
+}
+
+/// Close the file descriptors `first..=last`.
+///
+/// # Safety
+///
+/// The caller must own all of the file descriptors in the range.
+unsafe fn close_run(first: RawFilelike, last: RawFilelike) {
+    #[cfg(feature = "track-ownership")]
+    for raw in first..=last {
+        crate::track::close(raw, &Ok(()));
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if first != last && try_close_range(first, last) {
+        return;
+    }
+
+    for raw in first..=last {
+        libc::close(raw);
+    }
+}
+
+/// Call the `close_range` syscall directly, as older libcs, including glibc
+/// before 2.34, don't have a wrapper for it. This is also what
+/// [`example_ffi::close_range`] calls.
+///
+/// # Safety
+///
+/// The caller must own all of the file descriptors in `first..=last`.
+///
+/// [`example_ffi::close_range`]: crate::example_ffi::close_range
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[inline]
+pub(crate) unsafe fn close_range(
+    first: libc::c_uint,
+    last: libc::c_uint,
+    flags: libc::c_uint,
+) -> libc::c_int {
+    libc::syscall(libc::SYS_close_range, first, last, flags) as libc::c_int
+}
+
+/// Close the file descriptors `first..=last` with `close_range`, returning
+/// whether it succeeded.
+///
+/// # Safety
+///
+/// The caller must own all of the file descriptors in the range.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+unsafe fn try_close_range(first: RawFilelike, last: RawFilelike) -> bool {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    // Whether we've found that `close_range` isn't available, so that we
+    // don't keep trying it.
+    static UNAVAILABLE: AtomicBool = AtomicBool::new(false);
+
+    if UNAVAILABLE.load(Ordering::Relaxed) {
+        return false;
+    }
+
+    if close_range(first as libc::c_uint, last as libc::c_uint, 0) == 0 {
+        return true;
+    }
+
+    // `close_range` only fails if it isn't supported, or if its arguments
+    // are invalid, in which case it hasn't closed anything.
+    if std::io::Error::last_os_error().raw_os_error() == Some(libc::ENOSYS) {
+        UNAVAILABLE.store(true, Ordering::Relaxed);
+    }
+    false
+}
+
+impl FromIterator<OwnedFd> for OwnedFdSet {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = OwnedFd>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<OwnedFd> for OwnedFdSet {
+    #[inline]
+    fn extend<I: IntoIterator<Item = OwnedFd>>(&mut self, iter: I) {
+        for fd in iter {
+            self.insert(fd);
+        }
+    }
+}
+
+impl IntoIterator for OwnedFdSet {
+    type Item = OwnedFd;
+    type IntoIter = IntoIter;
+
+    #[inline]
+    fn into_iter(mut self) -> Self::IntoIter {
+        IntoIter {
+            inner: take(&mut self.fds).into_values(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a OwnedFdSet {
+    type Item = BorrowedFd<'a>;
+    type IntoIter = Iter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl fmt::Debug for OwnedFdSet {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.fds.keys()).finish()
+    }
+}
+
+/// An iterator over the file descriptors in an [`OwnedFdSet`], borrowing
+/// them.
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    inner: btree_map::Values<'a, RawFilelike, OwnedFd>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = BorrowedFd<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(AsFd::as_fd)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(AsFd::as_fd)
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+/// An iterator which moves the file descriptors out of an [`OwnedFdSet`].
+///
+/// Any file descriptors which aren't consumed are closed individually when
+/// the iterator is dropped.
+#[derive(Debug)]
+pub struct IntoIter {
+    inner: btree_map::IntoValues<RawFilelike, OwnedFd>,
+}
+
+impl Iterator for IntoIter {
+    type Item = OwnedFd;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for IntoIter {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+impl FusedIterator for IntoIter {}
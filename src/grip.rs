@@ -0,0 +1,469 @@
+//! Portability abstractions over filelike and socketlike objects.
+//!
+//! [`OwnedFilelike`] and [`OwnedSocketlike`] are the same type on Unix, but
+//! distinct types on Windows, so code which accepts "either a file or a
+//! socket" has to pick one. A *grip* can hold either, and can report which
+//! one it is.
+//!
+//! On Unix, [`OwnedGrip`] and [`BorrowedGrip`] are [`OwnedFd`] and
+//! [`BorrowedFd`], and [`AsGrip`], [`IntoGrip`], and [`FromGrip`] are
+//! implemented for everything which implements [`AsFilelike`] or
+//! [`AsSocketlike`]. On Windows, they're enums holding either a handle or a
+//! socket, and the traits are implemented for the standard library's handle
+//! and socket types.
+//!
+//! # Example
+//!
+//! ```rust
+//! use io_lifetimes::grip::{AsGrip, GripView};
+//! use std::fs::File;
+//! use std::io::Read;
+//! use std::net::TcpStream;
+//!
+//! fn read_some<G: AsGrip>(grip: &G, buf: &mut [u8]) -> std::io::Result<usize> {
+//!     match grip.as_grip_view::<File, TcpStream>() {
+//!         GripView::Filelike(file) => (&*file).read(buf),
+//!         GripView::Socketlike(socket) => (&*socket).read(buf),
+//!     }
+//! }
+//!
+//! let file = File::open("Cargo.toml")?;
+//! let mut buf = [0_u8; 8];
+//! assert_eq!(read_some(&file, &mut buf)?, 8);
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! [`OwnedFilelike`]: crate::OwnedFilelike
+//! [`OwnedSocketlike`]: crate::OwnedSocketlike
+//! [`OwnedFd`]: https://doc.rust-lang.org/stable/std/os/fd/struct.OwnedFd.html
+//! [`BorrowedFd`]: https://doc.rust-lang.org/stable/std/os/fd/struct.BorrowedFd.html
+//! [`AsFilelike`]: crate::AsFilelike
+//! [`AsSocketlike`]: crate::AsSocketlike
+
+use crate::views::{FilelikeView, FilelikeViewType, SocketlikeView, SocketlikeViewType};
+#[cfg(unix)]
+use crate::{AsFd, BorrowedFd, OwnedFd};
+#[cfg(windows)]
+use crate::{AsHandle, AsSocket, BorrowedHandle, BorrowedSocket, OwnedHandle, OwnedSocket};
+
+/// Whether a grip holds a filelike or a socketlike object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GripKind {
+    /// A filelike object, such as a file, pipe, or character device.
+    Filelike,
+
+    /// A socketlike object.
+    Socketlike,
+}
+
+/// A view of a grip, as either a `FilelikeView` of `F` or a `SocketlikeView`
+/// of `S`, depending on what kind of resource it holds.
+///
+/// This is returned by [`AsGrip::as_grip_view`].
+#[derive(Debug)]
+pub enum GripView<'grip, F: FilelikeViewType, S: SocketlikeViewType> {
+    /// The grip holds a filelike object.
+    Filelike(FilelikeView<'grip, F>),
+
+    /// The grip holds a socketlike object.
+    Socketlike(SocketlikeView<'grip, S>),
+}
+
+impl<F: FilelikeViewType, S: SocketlikeViewType> GripView<'_, F, S> {
+    /// Return which kind of view this is.
+    #[inline]
+    pub fn kind(&self) -> GripKind {
+        match self {
+            Self::Filelike(_) => GripKind::Filelike,
+            Self::Socketlike(_) => GripKind::Socketlike,
+        }
+    }
+}
+
+/// A reference to a filelike or socketlike object.
+///
+/// This is a portability abstraction over Unix-like [`BorrowedFd`] and
+/// Windows' `BorrowedHandle` and `BorrowedSocket`.
+#[cfg(unix)]
+pub type BorrowedGrip<'grip> = BorrowedFd<'grip>;
+
+/// A reference to a filelike or socketlike object.
+///
+/// This is a portability abstraction over Unix-like `BorrowedFd` and
+/// Windows' [`BorrowedHandle`] and [`BorrowedSocket`].
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy)]
+pub enum BorrowedGrip<'grip> {
+    /// A borrowed handle.
+    Handle(BorrowedHandle<'grip>),
+
+    /// A borrowed socket.
+    Socket(BorrowedSocket<'grip>),
+}
+
+/// An owned filelike or socketlike object.
+///
+/// This is a portability abstraction over Unix-like [`OwnedFd`] and Windows'
+/// `OwnedHandle` and `OwnedSocket`.
+#[cfg(unix)]
+pub type OwnedGrip = OwnedFd;
+
+/// An owned filelike or socketlike object.
+///
+/// This is a portability abstraction over Unix-like `OwnedFd` and Windows'
+/// [`OwnedHandle`] and [`OwnedSocket`].
+#[cfg(windows)]
+#[derive(Debug)]
+pub enum OwnedGrip {
+    /// An owned handle.
+    Handle(OwnedHandle),
+
+    /// An owned socket.
+    Socket(OwnedSocket),
+}
+
+#[cfg(windows)]
+impl BorrowedGrip<'_> {
+    /// Return whether this holds a handle or a socket.
+    #[inline]
+    pub fn kind(&self) -> GripKind {
+        match self {
+            Self::Handle(_) => GripKind::Filelike,
+            Self::Socket(_) => GripKind::Socketlike,
+        }
+    }
+}
+
+#[cfg(windows)]
+impl OwnedGrip {
+    /// Return whether this holds a handle or a socket.
+    #[inline]
+    pub fn kind(&self) -> GripKind {
+        match self {
+            Self::Handle(_) => GripKind::Filelike,
+            Self::Socket(_) => GripKind::Socketlike,
+        }
+    }
+}
+
+#[cfg(windows)]
+impl From<OwnedHandle> for OwnedGrip {
+    #[inline]
+    fn from(owned: OwnedHandle) -> Self {
+        Self::Handle(owned)
+    }
+}
+
+#[cfg(windows)]
+impl From<OwnedSocket> for OwnedGrip {
+    #[inline]
+    fn from(owned: OwnedSocket) -> Self {
+        Self::Socket(owned)
+    }
+}
+
+#[cfg(windows)]
+impl<'grip> From<BorrowedHandle<'grip>> for BorrowedGrip<'grip> {
+    #[inline]
+    fn from(borrowed: BorrowedHandle<'grip>) -> Self {
+        Self::Handle(borrowed)
+    }
+}
+
+#[cfg(windows)]
+impl<'grip> From<BorrowedSocket<'grip>> for BorrowedGrip<'grip> {
+    #[inline]
+    fn from(borrowed: BorrowedSocket<'grip>) -> Self {
+        Self::Socket(borrowed)
+    }
+}
+
+/// A portable trait to borrow a filelike or socketlike object from an
+/// underlying object.
+///
+/// This is a portability abstraction over Unix-like [`AsFd`] and Windows'
+/// `AsHandle` and `AsSocket`.
+#[cfg(unix)]
+pub trait AsGrip: AsFd {
+    /// Borrows the reference.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use std::fs::File;
+    /// # use std::io;
+    /// use io_lifetimes::grip::{AsGrip, BorrowedGrip};
+    ///
+    /// let mut f = File::open("foo.txt")?;
+    /// let borrowed_grip: BorrowedGrip<'_> = f.as_grip();
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    fn as_grip(&self) -> BorrowedGrip<'_>;
+
+    /// Return whether this is a filelike or a socketlike object.
+    ///
+    /// On Unix, this queries the OS for the file type, and reports sockets
+    /// as socketlike and everything else as filelike.
+    fn grip_kind(&self) -> GripKind;
+
+    /// Return a borrowing view of a resource which dereferences to a `&F` if
+    /// it's filelike, or a `&S` if it's socketlike.
+    fn as_grip_view<F: FilelikeViewType, S: SocketlikeViewType>(&self) -> GripView<'_, F, S>;
+}
+
+#[cfg(unix)]
+impl<T: AsFd> AsGrip for T {
+    #[inline]
+    fn as_grip(&self) -> BorrowedGrip<'_> {
+        self.as_fd()
+    }
+
+    #[inline]
+    fn grip_kind(&self) -> GripKind {
+        use crate::AsFilelike;
+        use std::os::unix::fs::FileTypeExt;
+
+        match self.as_filelike_view::<std::fs::File>().metadata() {
+            Ok(metadata) if metadata.file_type().is_socket() => GripKind::Socketlike,
+            _ => GripKind::Filelike,
+        }
+    }
+
+    #[inline]
+    fn as_grip_view<F: FilelikeViewType, S: SocketlikeViewType>(&self) -> GripView<'_, F, S> {
+        match self.grip_kind() {
+            GripKind::Filelike => GripView::Filelike(FilelikeView::new(self)),
+            GripKind::Socketlike => GripView::Socketlike(SocketlikeView::new(self)),
+        }
+    }
+}
+
+/// A portable trait to borrow a filelike or socketlike object from an
+/// underlying object.
+///
+/// This is a portability abstraction over Unix-like `AsFd` and Windows'
+/// [`AsHandle`] and [`AsSocket`]. On Windows, it's implemented for the
+/// standard library's handle and socket types, and for [`OwnedGrip`] and
+/// [`BorrowedGrip`].
+#[cfg(windows)]
+pub trait AsGrip {
+    /// Borrows the reference.
+    fn as_grip(&self) -> BorrowedGrip<'_>;
+
+    /// Return whether this is a filelike or a socketlike object.
+    #[inline]
+    fn grip_kind(&self) -> GripKind {
+        self.as_grip().kind()
+    }
+
+    /// Return a borrowing view of a resource which dereferences to a `&F` if
+    /// it's filelike, or a `&S` if it's socketlike.
+    #[inline]
+    fn as_grip_view<F: FilelikeViewType, S: SocketlikeViewType>(&self) -> GripView<'_, F, S> {
+        match self.as_grip() {
+            BorrowedGrip::Handle(handle) => GripView::Filelike(FilelikeView::new(&handle)),
+            BorrowedGrip::Socket(socket) => GripView::Socketlike(SocketlikeView::new(&socket)),
+        }
+    }
+}
+
+/// A portable trait to express the ability to consume an object and acquire
+/// ownership of its filelike or socketlike object.
+///
+/// This is a portability abstraction over Unix-like [`Into<OwnedFd>`] and
+/// Windows' `Into<OwnedHandle>` and `Into<OwnedSocket>`.
+#[cfg(unix)]
+pub trait IntoGrip: Into<OwnedFd> {
+    /// Consumes this object, returning the underlying filelike or socketlike
+    /// object.
+    fn into_grip(self) -> OwnedGrip;
+}
+
+#[cfg(unix)]
+impl<T: Into<OwnedFd>> IntoGrip for T {
+    #[inline]
+    fn into_grip(self) -> OwnedGrip {
+        crate::IntoFilelike::into_filelike(self)
+    }
+}
+
+/// A portable trait to express the ability to consume an object and acquire
+/// ownership of its filelike or socketlike object.
+///
+/// This is a portability abstraction over Unix-like `Into<OwnedFd>` and
+/// Windows' [`Into<OwnedHandle>`] and [`Into<OwnedSocket>`].
+#[cfg(windows)]
+pub trait IntoGrip {
+    /// Consumes this object, returning the underlying filelike or socketlike
+    /// object.
+    fn into_grip(self) -> OwnedGrip;
+}
+
+/// A portable trait to express the ability to construct an object from a
+/// filelike or socketlike object.
+///
+/// This is a portability abstraction over Unix-like [`From<OwnedFd>`] and
+/// Windows' `From<OwnedHandle>` and `From<OwnedSocket>`.
+#[cfg(unix)]
+pub trait FromGrip: From<OwnedFd> {
+    /// Constructs a new instance of `Self` from the given filelike or
+    /// socketlike object.
+    fn from_grip(owned: OwnedGrip) -> Self;
+
+    /// Constructs a new instance of `Self` from the given filelike or
+    /// socketlike object converted from `into_owned`.
+    fn from_into_grip<Owned: IntoGrip>(owned: Owned) -> Self;
+}
+
+#[cfg(unix)]
+impl<T: From<OwnedFd>> FromGrip for T {
+    #[inline]
+    fn from_grip(owned: OwnedGrip) -> Self {
+        crate::FromFilelike::from_filelike(owned)
+    }
+
+    #[inline]
+    fn from_into_grip<Owned: IntoGrip>(owned: Owned) -> Self {
+        Self::from_grip(owned.into_grip())
+    }
+}
+
+/// A portable trait to express the ability to construct an object from a
+/// filelike or socketlike object.
+///
+/// This is a portability abstraction over Unix-like `From<OwnedFd>` and
+/// Windows' [`From<OwnedHandle>`] and [`From<OwnedSocket>`]. On Windows, a
+/// type can't in general be constructed from either a handle or a socket, so
+/// this is only implemented for [`OwnedGrip`].
+#[cfg(windows)]
+pub trait FromGrip {
+    /// Constructs a new instance of `Self` from the given filelike or
+    /// socketlike object.
+    fn from_grip(owned: OwnedGrip) -> Self;
+
+    /// Constructs a new instance of `Self` from the given filelike or
+    /// socketlike object converted from `into_owned`.
+    #[inline]
+    fn from_into_grip<Owned: IntoGrip>(owned: Owned) -> Self
+    where
+        Self: Sized,
+    {
+        Self::from_grip(owned.into_grip())
+    }
+}
+
+#[cfg(windows)]
+impl FromGrip for OwnedGrip {
+    #[inline]
+    fn from_grip(owned: OwnedGrip) -> Self {
+        owned
+    }
+}
+
+#[cfg(windows)]
+impl AsGrip for OwnedGrip {
+    #[inline]
+    fn as_grip(&self) -> BorrowedGrip<'_> {
+        match self {
+            Self::Handle(handle) => BorrowedGrip::Handle(handle.as_handle()),
+            Self::Socket(socket) => BorrowedGrip::Socket(socket.as_socket()),
+        }
+    }
+}
+
+#[cfg(windows)]
+impl AsGrip for BorrowedGrip<'_> {
+    #[inline]
+    fn as_grip(&self) -> BorrowedGrip<'_> {
+        *self
+    }
+}
+
+#[cfg(windows)]
+impl IntoGrip for OwnedGrip {
+    #[inline]
+    fn into_grip(self) -> OwnedGrip {
+        self
+    }
+}
+
+#[cfg(windows)]
+impl<T: AsGrip + ?Sized> AsGrip for &T {
+    #[inline]
+    fn as_grip(&self) -> BorrowedGrip<'_> {
+        T::as_grip(self)
+    }
+}
+
+#[cfg(windows)]
+impl<T: AsGrip + ?Sized> AsGrip for &mut T {
+    #[inline]
+    fn as_grip(&self) -> BorrowedGrip<'_> {
+        T::as_grip(self)
+    }
+}
+
+/// Implement `AsGrip` for types which implement `AsHandle`, and `IntoGrip`
+/// for those which also implement `Into<OwnedHandle>`.
+#[cfg(windows)]
+macro_rules! impl_handle_grip {
+    (as: $($as_ty:ty),*; into: $($into_ty:ty),*) => {
+        $(
+            impl AsGrip for $as_ty {
+                #[inline]
+                fn as_grip(&self) -> BorrowedGrip<'_> {
+                    BorrowedGrip::Handle(self.as_handle())
+                }
+            }
+        )*
+        $(
+            impl IntoGrip for $into_ty {
+                #[inline]
+                fn into_grip(self) -> OwnedGrip {
+                    OwnedGrip::Handle(crate::IntoFilelike::into_filelike(self))
+                }
+            }
+        )*
+    };
+}
+
+/// Implement `AsGrip` for types which implement `AsSocket`, and `IntoGrip`
+/// for those which also implement `Into<OwnedSocket>`.
+#[cfg(windows)]
+macro_rules! impl_socket_grip {
+    (as: $($as_ty:ty),*; into: $($into_ty:ty),*) => {
+        $(
+            impl AsGrip for $as_ty {
+                #[inline]
+                fn as_grip(&self) -> BorrowedGrip<'_> {
+                    BorrowedGrip::Socket(self.as_socket())
+                }
+            }
+        )*
+        $(
+            impl IntoGrip for $into_ty {
+                #[inline]
+                fn into_grip(self) -> OwnedGrip {
+                    OwnedGrip::Socket(crate::IntoSocketlike::into_socketlike(self))
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(windows)]
+impl_handle_grip!(
+    as: OwnedHandle, BorrowedHandle<'_>, std::fs::File, std::io::Stdin,
+        std::io::StdinLock<'_>, std::io::Stdout, std::io::StdoutLock<'_>, std::io::Stderr,
+        std::io::StderrLock<'_>, std::process::ChildStdin, std::process::ChildStdout,
+        std::process::ChildStderr;
+    into: OwnedHandle, std::fs::File, std::process::ChildStdin, std::process::ChildStdout,
+        std::process::ChildStderr
+);
+
+#[cfg(windows)]
+impl_socket_grip!(
+    as: OwnedSocket, BorrowedSocket<'_>, std::net::TcpStream, std::net::TcpListener,
+        std::net::UdpSocket;
+    into: OwnedSocket, std::net::TcpStream, std::net::TcpListener, std::net::UdpSocket
+);
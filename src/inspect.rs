@@ -0,0 +1,248 @@
+//! Runtime inspection of file descriptors.
+//!
+//! This queries the OS for properties of a file descriptor, such as its file
+//! type or socket type, so that typed views can check that a resource is of
+//! the kind their target type expects. Deciding what kind of resource a file
+//! descriptor refers to is done by [`crate::kind::classify`]; the checks here
+//! build on that.
+
+use crate::kind::{classify, Kind};
+use crate::open_fds::AccessMode;
+use crate::views::{
+    FilelikeDescription, SocketDomain, SocketType, SocketlikeDescription, TryViewError,
+};
+use crate::BorrowedFd;
+use libc::{c_int, c_void, socklen_t};
+use std::io;
+use std::mem::{size_of, MaybeUninit};
+use std::os::unix::io::AsRawFd;
+
+/// Return the file type bits of `st_mode` for `fd`.
+pub(crate) fn file_type(fd: BorrowedFd<'_>) -> io::Result<libc::mode_t> {
+    let mut stat = MaybeUninit::<libc::stat>::uninit();
+
+    // Safety: `fd` is a valid file descriptor, and `stat` is a valid buffer
+    // for `fstat` to write to.
+    if unsafe { libc::fstat(fd.as_raw_fd(), stat.as_mut_ptr()) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // Safety: `fstat` succeeded, so it initialized `stat`.
+    Ok(unsafe { stat.assume_init() }.st_mode & libc::S_IFMT)
+}
+
+/// Read an integer-valued socket option.
+fn getsockopt_int(fd: BorrowedFd<'_>, level: c_int, name: c_int) -> io::Result<c_int> {
+    let mut value: c_int = 0;
+    let mut len = size_of::<c_int>() as socklen_t;
+
+    // Safety: `fd` is a valid file descriptor, and `value` and `len` describe
+    // a valid buffer for `getsockopt` to write to.
+    if unsafe {
+        libc::getsockopt(
+            fd.as_raw_fd(),
+            level,
+            name,
+            (&mut value as *mut c_int).cast::<c_void>(),
+            &mut len,
+        )
+    } == -1
+    {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(value)
+}
+
+/// Return the socket type of `fd`, or `None` if it's a type we don't know.
+pub(crate) fn socket_type(fd: BorrowedFd<'_>) -> io::Result<Option<SocketType>> {
+    Ok(match getsockopt_int(fd, libc::SOL_SOCKET, libc::SO_TYPE)? {
+        libc::SOCK_STREAM => Some(SocketType::Stream),
+        libc::SOCK_DGRAM => Some(SocketType::Datagram),
+        libc::SOCK_SEQPACKET => Some(SocketType::SeqPacket),
+        libc::SOCK_RAW => Some(SocketType::Raw),
+        _ => None,
+    })
+}
+
+/// Return the address family of `fd`, or `None` if the OS can't tell us.
+#[cfg(any(target_os = "android", target_os = "freebsd", target_os = "linux"))]
+pub(crate) fn socket_family(fd: BorrowedFd<'_>) -> io::Result<Option<c_int>> {
+    getsockopt_int(fd, libc::SOL_SOCKET, libc::SO_DOMAIN).map(Some)
+}
+
+/// Return the address family of `fd`, or `None` if the OS can't tell us.
+///
+/// This platform has no `SO_DOMAIN`, so we fall back to `getsockname`, which
+/// may return an empty address for an unbound socket.
+#[cfg(not(any(target_os = "android", target_os = "freebsd", target_os = "linux")))]
+pub(crate) fn socket_family(fd: BorrowedFd<'_>) -> io::Result<Option<c_int>> {
+    let mut storage = MaybeUninit::<libc::sockaddr_storage>::zeroed();
+    let mut len = size_of::<libc::sockaddr_storage>() as socklen_t;
+
+    // Safety: `fd` is a valid file descriptor, and `storage` and `len`
+    // describe a valid buffer for `getsockname` to write to.
+    if unsafe {
+        libc::getsockname(
+            fd.as_raw_fd(),
+            storage.as_mut_ptr().cast::<libc::sockaddr>(),
+            &mut len,
+        )
+    } == -1
+    {
+        return Err(io::Error::last_os_error());
+    }
+
+    if len == 0 {
+        return Ok(None);
+    }
+
+    // Safety: `storage` was zero-initialized, so it's fully initialized.
+    match c_int::from(unsafe { storage.assume_init() }.ss_family) {
+        libc::AF_UNSPEC => Ok(None),
+        family => Ok(Some(family)),
+    }
+}
+
+/// Return the domain for the address family `family`, or `None` if it's a
+/// domain we don't know.
+fn family_domain(family: c_int) -> Option<SocketDomain> {
+    match family {
+        libc::AF_INET | libc::AF_INET6 => Some(SocketDomain::Inet),
+        libc::AF_UNIX => Some(SocketDomain::Unix),
+        _ => None,
+    }
+}
+
+/// Return the domain of `fd`, or `None` if it's a domain we don't know or
+/// the OS can't tell us.
+pub(crate) fn socket_domain(fd: BorrowedFd<'_>) -> io::Result<Option<SocketDomain>> {
+    Ok(socket_family(fd)?.and_then(family_domain))
+}
+
+/// Test whether `fd` is a socket which is listening for connections.
+pub(crate) fn is_listening(fd: BorrowedFd<'_>) -> io::Result<bool> {
+    Ok(getsockopt_int(fd, libc::SOL_SOCKET, libc::SO_ACCEPTCONN)? != 0)
+}
+
+/// Test whether `fd` is in non-blocking mode.
+pub(crate) fn is_nonblocking(fd: BorrowedFd<'_>) -> io::Result<bool> {
+    // Safety: `fd` is a valid file descriptor, and `F_GETFL` takes no
+    // pointer arguments.
+    match unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL) } {
+        -1 => Err(io::Error::last_os_error()),
+        flags => Ok(flags & libc::O_NONBLOCK != 0),
+    }
+}
+
+/// Return the access mode of `fd`, or `None` if it can't be read from or
+/// written to, such as a Linux `O_PATH` file descriptor.
+pub(crate) fn access_mode(fd: BorrowedFd<'_>) -> io::Result<Option<AccessMode>> {
+    // Safety: `fd` is a valid file descriptor, and `F_GETFL` takes no
+    // pointer arguments.
+    let flags = match unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL) } {
+        -1 => return Err(io::Error::last_os_error()),
+        flags => flags,
+    };
+
+    Ok(AccessMode::from_flags(flags))
+}
+
+/// Put `fd` into non-blocking mode, or take it out of non-blocking mode.
+pub(crate) fn set_nonblocking(fd: BorrowedFd<'_>, nonblocking: bool) -> io::Result<()> {
+    let raw = fd.as_raw_fd();
+
+    // Safety: `raw` is a valid file descriptor, and `F_GETFL` and `F_SETFL`
+    // take no pointer arguments.
+    unsafe {
+        let flags = libc::fcntl(raw, libc::F_GETFL);
+        if flags == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        let flags = if nonblocking {
+            flags | libc::O_NONBLOCK
+        } else {
+            flags & !libc::O_NONBLOCK
+        };
+        if libc::fcntl(raw, libc::F_SETFL, flags) == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that `fd` matches `expected`.
+pub(crate) fn check_filelike(
+    fd: BorrowedFd<'_>,
+    expected: FilelikeDescription,
+    target: &'static str,
+) -> Result<(), TryViewError> {
+    let (matches, reason): (fn(Kind) -> bool, _) = match expected {
+        FilelikeDescription::Any => return Ok(()),
+        FilelikeDescription::RegularFile => {
+            (|kind| kind == Kind::RegularFile, "not a regular file")
+        }
+        FilelikeDescription::Directory => (|kind| kind == Kind::Directory, "not a directory"),
+        FilelikeDescription::Pipe => (|kind| kind == Kind::Pipe, "not a pipe"),
+        FilelikeDescription::CharacterDevice => {
+            (Kind::is_character_device, "not a character device")
+        }
+    };
+
+    if !matches(classify(fd)?) {
+        return Err(TryViewError::Mismatch { target, reason });
+    }
+
+    Ok(())
+}
+
+/// Check that `fd` matches `expected`.
+pub(crate) fn check_socketlike(
+    fd: BorrowedFd<'_>,
+    expected: SocketlikeDescription,
+    target: &'static str,
+) -> Result<(), TryViewError> {
+    let mismatch = |reason| Err(TryViewError::Mismatch { target, reason });
+
+    let socket_type = match classify(fd)? {
+        Kind::Socket(socket_type) => socket_type,
+        _ => return mismatch("not a socket"),
+    };
+
+    if let Some(want) = expected.socket_type {
+        if socket_type != Some(want) {
+            return mismatch(match want {
+                SocketType::Stream => "not a stream socket",
+                SocketType::Datagram => "not a datagram socket",
+                SocketType::SeqPacket => "not a sequenced-packet socket",
+                SocketType::Raw => "not a raw socket",
+            });
+        }
+    }
+
+    if let Some(want) = expected.domain {
+        // If the OS can't tell us the domain, give the socket the benefit of
+        // the doubt rather than reporting a mismatch.
+        if let Some(family) = socket_family(fd)? {
+            if family_domain(family) != Some(want) {
+                return mismatch(match want {
+                    SocketDomain::Inet => "not an IPv4 or IPv6 socket",
+                    SocketDomain::Unix => "not a Unix-domain socket",
+                });
+            }
+        }
+    }
+
+    if let Some(want) = expected.listening {
+        if is_listening(fd)? != want {
+            return mismatch(if want {
+                "not a listening socket"
+            } else {
+                "socket is listening"
+            });
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+//! Classifying filelike and socketlike objects.
+//!
+//! [`Classify::kind`] reports what kind of resource a filelike or socketlike
+//! object refers to, such as a regular file, a pipe, or a socket, so that
+//! generic code can choose a code path without converting to raw file
+//! descriptors.
+//!
+//! This is also the classification behind the checked views, such as
+//! [`AsFilelike::try_as_filelike_view`], the kinds in [`typed`], and the
+//! descriptions in [`describe`].
+//!
+//! [`typed`]: crate::typed
+//! [`describe`]: crate::describe
+//!
+//! # Example
+//!
+//! ```rust
+//! use io_lifetimes::kind::{Classify, Kind};
+//! use std::fs::File;
+//!
+//! let file = File::open("Cargo.toml")?;
+//! assert_eq!(file.kind()?, Kind::RegularFile);
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::views::SocketType;
+use crate::{AsFilelike, BorrowedFd};
+use std::io::{self, IsTerminal};
+
+/// The kind of resource a file descriptor refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Kind {
+    /// A regular file, as in `S_IFREG`.
+    RegularFile,
+
+    /// A directory, as in `S_IFDIR`.
+    Directory,
+
+    /// A pipe or FIFO, as in `S_IFIFO`.
+    Pipe,
+
+    /// A character device which isn't a terminal, as in `S_IFCHR`.
+    CharacterDevice,
+
+    /// A terminal, which is a character device for which `isatty` succeeds.
+    Tty,
+
+    /// A block device, as in `S_IFBLK`.
+    BlockDevice,
+
+    /// A socket, as in `S_IFSOCK`, along with its socket type, or `None` if
+    /// it's a type we don't know.
+    Socket(Option<SocketType>),
+
+    /// A symlink, as in `S_IFLNK`. This is only seen for file descriptors
+    /// opened with `O_PATH | O_NOFOLLOW`.
+    Symlink,
+
+    /// A file type we don't know.
+    Unknown,
+}
+
+impl Kind {
+    /// Test whether this is a [`Kind::Socket`] of any socket type.
+    #[inline]
+    pub fn is_socket(self) -> bool {
+        matches!(self, Self::Socket(_))
+    }
+
+    /// Test whether this is a [`Kind::CharacterDevice`] or a [`Kind::Tty`],
+    /// which are both character devices.
+    #[inline]
+    pub fn is_character_device(self) -> bool {
+        matches!(self, Self::CharacterDevice | Self::Tty)
+    }
+}
+
+/// An extension trait for querying the [`Kind`] of a filelike or socketlike
+/// object.
+///
+/// On Unix-family platforms, socketlike objects are file descriptors too, so
+/// this is implemented for everything which implements [`AsFilelike`],
+/// including everything which implements [`AsSocketlike`].
+///
+/// [`AsSocketlike`]: crate::AsSocketlike
+pub trait Classify: AsFilelike {
+    /// Query the OS for the kind of resource `self` refers to.
+    ///
+    /// This uses `fstat`, along with `isatty` for character devices and
+    /// `getsockopt` for sockets. The result is a snapshot; for example, a
+    /// path may be replaced by a different kind of file after it's opened,
+    /// but an open file descriptor always refers to the same resource.
+    fn kind(&self) -> io::Result<Kind>;
+}
+
+impl<T: AsFilelike> Classify for T {
+    #[inline]
+    fn kind(&self) -> io::Result<Kind> {
+        classify(self.as_filelike())
+    }
+}
+
+/// Query the OS for the kind of resource `fd` refers to.
+pub(crate) fn classify(fd: BorrowedFd<'_>) -> io::Result<Kind> {
+    Ok(match crate::inspect::file_type(fd)? {
+        libc::S_IFREG => Kind::RegularFile,
+        libc::S_IFDIR => Kind::Directory,
+        libc::S_IFIFO => Kind::Pipe,
+        libc::S_IFCHR if fd.is_terminal() => Kind::Tty,
+        libc::S_IFCHR => Kind::CharacterDevice,
+        libc::S_IFBLK => Kind::BlockDevice,
+        libc::S_IFSOCK => Kind::Socket(crate::inspect::socket_type(fd)?),
+        libc::S_IFLNK => Kind::Symlink,
+        _ => Kind::Unknown,
+    })
+}
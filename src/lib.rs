@@ -34,6 +34,10 @@
 #![cfg(any(unix, windows, target_os = "wasi", target_os = "hermit"))]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(all(feature = "close", any(unix, windows, target_os = "wasi")))]
+mod close;
+#[cfg(all(unix, feature = "close"))]
+mod inspect;
 mod portability;
 mod traits;
 
@@ -132,8 +136,67 @@ pub use portability::{
     IntoFilelike, IntoSocketlike, OwnedFilelike, OwnedSocketlike,
 };
 
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use io_lifetimes_derive::{
+    AsFilelike, AsSocketlike, FromFilelike, FromSocketlike, IntoFilelike, IntoSocketlike,
+};
+
+#[cfg(all(feature = "close", any(unix, windows, target_os = "wasi")))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(feature = "close", any(unix, windows, target_os = "wasi"))))
+)]
+pub use close::CloseChecked;
+
+#[cfg(all(unix, feature = "close"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+pub mod access;
+#[cfg(unix)]
+#[cfg_attr(docsrs, doc(cfg(unix)))]
+pub mod c_api;
+#[cfg(all(unix, feature = "close"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+pub mod channel;
+#[cfg(all(unix, feature = "close"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+pub mod command;
+#[cfg(all(unix, feature = "close"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+pub mod describe;
 #[cfg(feature = "close")]
 #[cfg_attr(docsrs, doc(cfg(feature = "close")))]
 pub mod example_ffi;
+#[cfg(all(unix, feature = "close"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+pub mod fd_set;
+#[cfg(any(unix, windows))]
+#[cfg_attr(docsrs, doc(cfg(any(unix, windows))))]
+pub mod grip;
+#[cfg(all(unix, feature = "close"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+pub mod kind;
+#[cfg(all(unix, feature = "close"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+pub mod open_fds;
 pub mod raw;
+#[cfg(all(unix, feature = "close"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+pub mod scm_rights;
+pub mod shared;
+#[cfg(all(unix, feature = "close"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+pub mod socket_activation;
+#[cfg(all(unix, feature = "close"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+pub mod stdio;
+#[cfg(all(unix, feature = "test-support"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "test-support"))))]
+pub mod test_support;
+#[cfg(all(unix, feature = "track-ownership"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "track-ownership"))))]
+pub mod track;
+#[cfg(all(unix, feature = "close"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+pub mod typed;
 pub mod views;
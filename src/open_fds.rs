@@ -0,0 +1,238 @@
+//! Enumerating the process' open file descriptors.
+//!
+//! [`open_fds`] lists the file descriptors which are open in the current
+//! process, along with some metadata about each one. This is useful for
+//! diagnostics, and for checking what a child process would inherit.
+//!
+//! The list is a snapshot. Other threads may open or close file descriptors
+//! at any time, so by the time the caller looks at an entry, its file
+//! descriptor may have been closed, or even reused for something else. This
+//! is why the entries are [`RawFilelike`] numbers rather than
+//! [`BorrowedFd`]s.
+//!
+//! # Example
+//!
+//! ```rust
+//! use io_lifetimes::open_fds::open_fds;
+//!
+//! for fd in open_fds()? {
+//!     println!("{}: {:?} {:?}", fd.raw_fd(), fd.file_type(), fd.target());
+//! }
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::raw::RawFilelike;
+use crate::{AsFilelike, BorrowedFd};
+use std::fs::{File, FileType};
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+/// The maximum number of file descriptors probed when `/proc/self/fd` isn't
+/// available and the file descriptor limit is unlimited or very large.
+const MAX_PROBE: RawFilelike = 1 << 16;
+
+/// The access mode a file descriptor was opened with, as in `O_ACCMODE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AccessMode {
+    /// Opened for reading only, as in `O_RDONLY`.
+    ReadOnly,
+
+    /// Opened for writing only, as in `O_WRONLY`.
+    WriteOnly,
+
+    /// Opened for reading and writing, as in `O_RDWR`.
+    ReadWrite,
+}
+
+impl AccessMode {
+    /// Decode the access mode from file status flags, as returned by
+    /// `fcntl(F_GETFL)`, returning `None` if the file descriptor can't be
+    /// read from or written to, such as a Linux `O_PATH` file descriptor.
+    pub(crate) fn from_flags(flags: libc::c_int) -> Option<Self> {
+        // `O_RDONLY` is zero, so `O_PATH` file descriptors would otherwise
+        // look read-only.
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        if flags & libc::O_PATH != 0 {
+            return None;
+        }
+
+        match flags & libc::O_ACCMODE {
+            libc::O_RDONLY => Some(Self::ReadOnly),
+            libc::O_WRONLY => Some(Self::WriteOnly),
+            libc::O_RDWR => Some(Self::ReadWrite),
+            _ => None,
+        }
+    }
+
+    /// Test whether this mode allows reading.
+    #[inline]
+    pub fn is_readable(self) -> bool {
+        matches!(self, Self::ReadOnly | Self::ReadWrite)
+    }
+
+    /// Test whether this mode allows writing.
+    #[inline]
+    pub fn is_writable(self) -> bool {
+        matches!(self, Self::WriteOnly | Self::ReadWrite)
+    }
+}
+
+/// An open file descriptor, as returned by [`open_fds`].
+#[derive(Debug, Clone)]
+pub struct OpenFd {
+    raw: RawFilelike,
+    file_type: FileType,
+    target: Option<PathBuf>,
+    cloexec: bool,
+    access_mode: Option<AccessMode>,
+}
+
+impl OpenFd {
+    /// Return the file descriptor number.
+    #[inline]
+    pub fn raw_fd(&self) -> RawFilelike {
+        self.raw
+    }
+
+    /// Return the type of the file the file descriptor refers to.
+    #[inline]
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    /// Return what the file descriptor refers to, as reported by
+    /// `readlink` on `/proc/self/fd`. This is a path for files and
+    /// directories, and a description such as `pipe:[1234]` for other
+    /// resources. This is `None` if it isn't available.
+    #[inline]
+    pub fn target(&self) -> Option<&Path> {
+        self.target.as_deref()
+    }
+
+    /// Test whether the file descriptor has close-on-exec set.
+    #[inline]
+    pub fn is_cloexec(&self) -> bool {
+        self.cloexec
+    }
+
+    /// Return the access mode the file descriptor was opened with, or `None`
+    /// if it can't be read from or written to, such as a Linux `O_PATH` file
+    /// descriptor, or if it isn't one we recognize.
+    #[inline]
+    pub fn access_mode(&self) -> Option<AccessMode> {
+        self.access_mode
+    }
+
+    /// Borrow the file descriptor.
+    ///
+    /// # Safety
+    ///
+    /// The file descriptor must still be open, and still refer to the same
+    /// resource it did when [`open_fds`] was called, and it must remain open
+    /// for the duration of the returned `BorrowedFd`.
+    #[inline]
+    pub unsafe fn borrow_raw(&self) -> BorrowedFd<'_> {
+        #[cfg(feature = "track-ownership")]
+        crate::track::borrow(self.raw);
+
+        BorrowedFd::borrow_raw(self.raw)
+    }
+}
+
+impl AsRawFd for OpenFd {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFilelike {
+        self.raw
+    }
+}
+
+/// List the open file descriptors in the current process, in ascending
+/// order.
+///
+/// This walks `/proc/self/fd` if it's available. Otherwise, it probes each
+/// file descriptor number up to the `RLIMIT_NOFILE` limit with
+/// `fcntl(F_GETFD)`, though it doesn't probe more than 65536.
+pub fn open_fds() -> io::Result<Vec<OpenFd>> {
+    let raws = match proc_self_fd() {
+        Ok(raws) => raws,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => probe()?,
+        Err(err) => return Err(err),
+    };
+
+    let mut fds = Vec::with_capacity(raws.len());
+    for raw in raws {
+        if let Some(fd) = inspect(raw)? {
+            fds.push(fd);
+        }
+    }
+    Ok(fds)
+}
+
+/// Read the file descriptor numbers from `/proc/self/fd`.
+fn proc_self_fd() -> io::Result<Vec<RawFilelike>> {
+    let mut raws = Vec::new();
+    for entry in std::fs::read_dir("/proc/self/fd")? {
+        if let Some(raw) = entry?.file_name().to_str().and_then(|s| s.parse().ok()) {
+            raws.push(raw);
+        }
+    }
+
+    // The list includes the file descriptor `read_dir` used, which is closed
+    // now; `inspect` skips it.
+    raws.sort_unstable();
+    Ok(raws)
+}
+
+/// Return all the file descriptor numbers that could be open.
+fn probe() -> io::Result<Vec<RawFilelike>> {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+
+    // Safety: `limit` is a valid buffer for `getrlimit` to write to.
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let max = RawFilelike::try_from(limit.rlim_cur).map_or(MAX_PROBE, |max| max.min(MAX_PROBE));
+    Ok((0..max).collect())
+}
+
+/// Gather the metadata for `raw`, or return `None` if it isn't open.
+fn inspect(raw: RawFilelike) -> io::Result<Option<OpenFd>> {
+    // Safety: `F_GETFD` and `F_GETFL` don't take pointer arguments, and are
+    // harmless to call on file descriptors which aren't open.
+    let (fd_flags, fl_flags) = unsafe {
+        (
+            libc::fcntl(raw, libc::F_GETFD),
+            libc::fcntl(raw, libc::F_GETFL),
+        )
+    };
+    if fd_flags == -1 || fl_flags == -1 {
+        let err = io::Error::last_os_error();
+        return match err.raw_os_error() {
+            Some(libc::EBADF) => Ok(None),
+            _ => Err(err),
+        };
+    }
+
+    // Safety: `raw` was open just now. We only use it for the duration of
+    // this `fstat`, and never close it.
+    let fd = unsafe { BorrowedFd::borrow_raw(raw) };
+    let file_type = match fd.as_filelike_view::<File>().metadata() {
+        Ok(metadata) => metadata.file_type(),
+        Err(err) if err.raw_os_error() == Some(libc::EBADF) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    Ok(Some(OpenFd {
+        raw,
+        file_type,
+        target: std::fs::read_link(format!("/proc/self/fd/{}", raw)).ok(),
+        cloexec: fd_flags & libc::FD_CLOEXEC != 0,
+        access_mode: AccessMode::from_flags(fl_flags),
+    }))
+}
@@ -4,11 +4,21 @@
 //! handles are distinct from socket descriptors. This file provides a minimal
 //! layer of portability over this difference.
 
-use crate::views::{FilelikeView, FilelikeViewType, SocketlikeView, SocketlikeViewType};
+#[cfg(all(unix, feature = "close"))]
+use crate::views::{
+    AsyncFilelikeView, AsyncFilelikeViewType, AsyncSocketlikeView, AsyncSocketlikeViewType,
+    TryViewError,
+};
+use crate::views::{
+    FilelikeView, FilelikeViewMut, FilelikeViewType, SocketlikeView, SocketlikeViewMut,
+    SocketlikeViewType,
+};
 #[cfg(any(unix, target_os = "wasi", target_os = "hermit"))]
 use crate::{AsFd, BorrowedFd, OwnedFd};
 #[cfg(windows)]
 use crate::{AsHandle, AsSocket, BorrowedHandle, BorrowedSocket, OwnedHandle, OwnedSocket};
+#[cfg(all(unix, feature = "close"))]
+use std::io;
 
 /// A reference to a filelike object.
 ///
@@ -105,6 +115,74 @@ pub trait AsFilelike: AsFd {
     /// [`Read`]: std::io::Read
     /// [`Write`]: std::io::Write
     fn as_filelike_view<Target: FilelikeViewType>(&self) -> FilelikeView<'_, Target>;
+
+    /// Return a borrowing view of a resource which dereferences to a
+    /// `&mut Target`.
+    ///
+    /// This is like [`as_filelike_view`], but exclusively borrows `self` so
+    /// that the view can provide access to methods which take `&mut self`,
+    /// such as [`Read`] and [`Write`]:
+    ///
+    /// ```rust,ignore
+    /// let mut v = f.as_filelike_view_mut::<std::fs::File>();
+    /// v.read(&mut buf).unwrap();
+    /// ```
+    ///
+    /// [`as_filelike_view`]: Self::as_filelike_view
+    /// [`Read`]: std::io::Read
+    /// [`Write`]: std::io::Write
+    fn as_filelike_view_mut<Target: FilelikeViewType>(&mut self) -> FilelikeViewMut<'_, Target>;
+
+    /// Return a borrowing view of a resource which dereferences to a
+    /// `&Target`, after checking at runtime that the resource is of the kind
+    /// described by `Target`'s [`FilelikeViewType::DESCRIPTION`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use std::fs::File;
+    /// # use std::io;
+    /// use io_lifetimes::AsFilelike;
+    ///
+    /// let f = File::open("foo.txt")?;
+    /// let view = f.try_as_filelike_view::<File>()?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    #[cfg(all(unix, feature = "close"))]
+    #[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+    fn try_as_filelike_view<Target: FilelikeViewType>(
+        &self,
+    ) -> Result<FilelikeView<'_, Target>, TryViewError>;
+
+    /// Return a borrowing view of a resource which dereferences to a
+    /// `&mut Target`, after checking at runtime that the resource is of the
+    /// kind described by `Target`'s [`FilelikeViewType::DESCRIPTION`].
+    ///
+    /// This is the checked counterpart of [`as_filelike_view_mut`].
+    ///
+    /// [`as_filelike_view_mut`]: Self::as_filelike_view_mut
+    #[cfg(all(unix, feature = "close"))]
+    #[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+    fn try_as_filelike_view_mut<Target: FilelikeViewType>(
+        &mut self,
+    ) -> Result<FilelikeViewMut<'_, Target>, TryViewError>;
+
+    /// Return a borrowing view of a resource which dereferences to an async
+    /// type such as `tokio::fs::File`, registered with the current async
+    /// runtime's reactor.
+    ///
+    /// The view holds a duplicate of the resource, so dropping it doesn't
+    /// close the resource. This must be called from within the runtime
+    /// context the target type requires. The duplicate shares the resource's
+    /// non-blocking flag, which the target type may set for as long as the
+    /// view exists; if the resource was in blocking mode, it's returned to
+    /// blocking mode when the view is dropped. Views of the same resource
+    /// shouldn't overlap.
+    #[cfg(all(unix, feature = "close"))]
+    #[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+    fn as_async_filelike_view<Target: AsyncFilelikeViewType>(
+        &self,
+    ) -> io::Result<AsyncFilelikeView<'_, Target>>;
 }
 
 #[cfg(any(unix, target_os = "wasi", target_os = "hermit"))]
@@ -118,6 +196,35 @@ impl<T: AsFd> AsFilelike for T {
     fn as_filelike_view<Target: FilelikeViewType>(&self) -> FilelikeView<'_, Target> {
         FilelikeView::new(self)
     }
+
+    #[inline]
+    fn as_filelike_view_mut<Target: FilelikeViewType>(&mut self) -> FilelikeViewMut<'_, Target> {
+        FilelikeViewMut::new(self)
+    }
+
+    #[cfg(all(unix, feature = "close"))]
+    #[inline]
+    fn try_as_filelike_view<Target: FilelikeViewType>(
+        &self,
+    ) -> Result<FilelikeView<'_, Target>, TryViewError> {
+        FilelikeView::try_new(self)
+    }
+
+    #[cfg(all(unix, feature = "close"))]
+    #[inline]
+    fn try_as_filelike_view_mut<Target: FilelikeViewType>(
+        &mut self,
+    ) -> Result<FilelikeViewMut<'_, Target>, TryViewError> {
+        FilelikeViewMut::try_new(self)
+    }
+
+    #[cfg(all(unix, feature = "close"))]
+    #[inline]
+    fn as_async_filelike_view<Target: AsyncFilelikeViewType>(
+        &self,
+    ) -> io::Result<AsyncFilelikeView<'_, Target>> {
+        AsyncFilelikeView::new(self)
+    }
 }
 
 /// A portable trait to borrow a reference from an underlying filelike object.
@@ -159,6 +266,23 @@ pub trait AsFilelike: AsHandle {
     /// [`Read`]: std::io::Read
     /// [`Write`]: std::io::Write
     fn as_filelike_view<Target: FilelikeViewType>(&self) -> FilelikeView<'_, Target>;
+
+    /// Return a borrowing view of a resource which dereferences to a
+    /// `&mut Target`.
+    ///
+    /// This is like [`as_filelike_view`], but exclusively borrows `self` so
+    /// that the view can provide access to methods which take `&mut self`,
+    /// such as [`Read`] and [`Write`]:
+    ///
+    /// ```rust,ignore
+    /// let mut v = f.as_filelike_view_mut::<std::fs::File>();
+    /// v.read(&mut buf).unwrap();
+    /// ```
+    ///
+    /// [`as_filelike_view`]: Self::as_filelike_view
+    /// [`Read`]: std::io::Read
+    /// [`Write`]: std::io::Write
+    fn as_filelike_view_mut<Target: FilelikeViewType>(&mut self) -> FilelikeViewMut<'_, Target>;
 }
 
 #[cfg(windows)]
@@ -172,6 +296,11 @@ impl<T: AsHandle> AsFilelike for T {
     fn as_filelike_view<Target: FilelikeViewType>(&self) -> FilelikeView<'_, Target> {
         FilelikeView::new(self)
     }
+
+    #[inline]
+    fn as_filelike_view_mut<Target: FilelikeViewType>(&mut self) -> FilelikeViewMut<'_, Target> {
+        FilelikeViewMut::new(self)
+    }
 }
 
 /// A portable trait to borrow a reference from an underlying socketlike
@@ -202,6 +331,93 @@ pub trait AsSocketlike: AsFd {
     /// [`Read`]: std::io::Read
     /// [`Write`]: std::io::Write
     fn as_socketlike_view<Target: SocketlikeViewType>(&self) -> SocketlikeView<'_, Target>;
+
+    /// Return a borrowing view of a resource which dereferences to a
+    /// `&mut Target`.
+    ///
+    /// This is like [`as_socketlike_view`], but exclusively borrows `self` so
+    /// that the view can provide access to methods which take `&mut self`,
+    /// such as [`Read`] and [`Write`]:
+    ///
+    /// ```rust,ignore
+    /// let mut v = s.as_socketlike_view_mut::<std::net::TcpStream>();
+    /// v.read(&mut buf).unwrap();
+    /// ```
+    ///
+    /// [`as_socketlike_view`]: Self::as_socketlike_view
+    /// [`Read`]: std::io::Read
+    /// [`Write`]: std::io::Write
+    fn as_socketlike_view_mut<Target: SocketlikeViewType>(
+        &mut self,
+    ) -> SocketlikeViewMut<'_, Target>;
+
+    /// Return a borrowing view of a resource which dereferences to a
+    /// `&Target`, after checking at runtime that the resource is of the kind
+    /// described by `Target`'s [`SocketlikeViewType::DESCRIPTION`], such as
+    /// its socket type, domain, and whether it's listening.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::net::{TcpListener, TcpStream};
+    /// # use std::io;
+    /// use io_lifetimes::AsSocketlike;
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:0")?;
+    /// assert!(listener.try_as_socketlike_view::<TcpListener>().is_ok());
+    /// assert!(listener.try_as_socketlike_view::<TcpStream>().is_err());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    #[cfg(all(unix, feature = "close"))]
+    #[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+    fn try_as_socketlike_view<Target: SocketlikeViewType>(
+        &self,
+    ) -> Result<SocketlikeView<'_, Target>, TryViewError>;
+
+    /// Return a borrowing view of a resource which dereferences to a
+    /// `&mut Target`, after checking at runtime that the resource is of the
+    /// kind described by `Target`'s [`SocketlikeViewType::DESCRIPTION`].
+    ///
+    /// This is the checked counterpart of [`as_socketlike_view_mut`].
+    ///
+    /// [`as_socketlike_view_mut`]: Self::as_socketlike_view_mut
+    #[cfg(all(unix, feature = "close"))]
+    #[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+    fn try_as_socketlike_view_mut<Target: SocketlikeViewType>(
+        &mut self,
+    ) -> Result<SocketlikeViewMut<'_, Target>, TryViewError>;
+
+    /// Return a borrowing view of a resource which dereferences to an async
+    /// type such as `tokio::net::TcpStream`, registered with the current
+    /// async runtime's reactor.
+    ///
+    /// The view holds a duplicate of the resource, so dropping it doesn't
+    /// close the resource. This must be called from within the runtime
+    /// context the target type requires. The duplicate shares the resource's
+    /// non-blocking flag, which the target type may set for as long as the
+    /// view exists; if the resource was in blocking mode, it's returned to
+    /// blocking mode when the view is dropped. Views of the same resource
+    /// shouldn't overlap.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "async-std")]
+    /// # {
+    /// use io_lifetimes::AsSocketlike;
+    /// use std::net::TcpListener;
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:0")?;
+    /// let view = listener.as_async_socketlike_view::<async_std::net::TcpListener>()?;
+    /// assert_eq!(view.local_addr()?, listener.local_addr()?);
+    /// # }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[cfg(all(unix, feature = "close"))]
+    #[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+    fn as_async_socketlike_view<Target: AsyncSocketlikeViewType>(
+        &self,
+    ) -> io::Result<AsyncSocketlikeView<'_, Target>>;
 }
 
 #[cfg(any(unix, target_os = "wasi", target_os = "hermit"))]
@@ -215,6 +431,37 @@ impl<T: AsFd> AsSocketlike for T {
     fn as_socketlike_view<Target: SocketlikeViewType>(&self) -> SocketlikeView<'_, Target> {
         SocketlikeView::new(self)
     }
+
+    #[inline]
+    fn as_socketlike_view_mut<Target: SocketlikeViewType>(
+        &mut self,
+    ) -> SocketlikeViewMut<'_, Target> {
+        SocketlikeViewMut::new(self)
+    }
+
+    #[cfg(all(unix, feature = "close"))]
+    #[inline]
+    fn try_as_socketlike_view<Target: SocketlikeViewType>(
+        &self,
+    ) -> Result<SocketlikeView<'_, Target>, TryViewError> {
+        SocketlikeView::try_new(self)
+    }
+
+    #[cfg(all(unix, feature = "close"))]
+    #[inline]
+    fn try_as_socketlike_view_mut<Target: SocketlikeViewType>(
+        &mut self,
+    ) -> Result<SocketlikeViewMut<'_, Target>, TryViewError> {
+        SocketlikeViewMut::try_new(self)
+    }
+
+    #[cfg(all(unix, feature = "close"))]
+    #[inline]
+    fn as_async_socketlike_view<Target: AsyncSocketlikeViewType>(
+        &self,
+    ) -> io::Result<AsyncSocketlikeView<'_, Target>> {
+        AsyncSocketlikeView::new(self)
+    }
 }
 
 /// A portable trait to borrow a reference from an underlying socketlike
@@ -243,6 +490,25 @@ pub trait AsSocketlike: AsSocket {
     ///
     /// [`TcpStream`]: std::net::TcpStream
     fn as_socketlike_view<Target: SocketlikeViewType>(&self) -> SocketlikeView<'_, Target>;
+
+    /// Return a borrowing view of a resource which dereferences to a
+    /// `&mut Target`.
+    ///
+    /// This is like [`as_socketlike_view`], but exclusively borrows `self` so
+    /// that the view can provide access to methods which take `&mut self`,
+    /// such as [`Read`] and [`Write`]:
+    ///
+    /// ```rust,ignore
+    /// let mut v = s.as_socketlike_view_mut::<std::net::TcpStream>();
+    /// v.read(&mut buf).unwrap();
+    /// ```
+    ///
+    /// [`as_socketlike_view`]: Self::as_socketlike_view
+    /// [`Read`]: std::io::Read
+    /// [`Write`]: std::io::Write
+    fn as_socketlike_view_mut<Target: SocketlikeViewType>(
+        &mut self,
+    ) -> SocketlikeViewMut<'_, Target>;
 }
 
 #[cfg(windows)]
@@ -256,6 +522,13 @@ impl<T: AsSocket> AsSocketlike for T {
     fn as_socketlike_view<Target: SocketlikeViewType>(&self) -> SocketlikeView<'_, Target> {
         SocketlikeView::new(self)
     }
+
+    #[inline]
+    fn as_socketlike_view_mut<Target: SocketlikeViewType>(
+        &mut self,
+    ) -> SocketlikeViewMut<'_, Target> {
+        SocketlikeViewMut::new(self)
+    }
 }
 
 /// A portable trait to express the ability to consume an object and acquire
@@ -285,7 +558,10 @@ pub trait IntoFilelike: Into<OwnedFd> {
 impl<T: Into<OwnedFd>> IntoFilelike for T {
     #[inline]
     fn into_filelike(self) -> OwnedFilelike {
-        self.into()
+        let owned = self.into();
+        #[cfg(all(unix, feature = "track-ownership"))]
+        crate::track::release(std::os::unix::io::AsRawFd::as_raw_fd(&owned));
+        owned
     }
 }
 
@@ -323,7 +599,10 @@ pub trait IntoSocketlike: Into<OwnedFd> {
 impl<T: Into<OwnedFd>> IntoSocketlike for T {
     #[inline]
     fn into_socketlike(self) -> OwnedSocketlike {
-        self.into()
+        let owned = self.into();
+        #[cfg(all(unix, feature = "track-ownership"))]
+        crate::track::release(std::os::unix::io::AsRawFd::as_raw_fd(&owned));
+        owned
     }
 }
 
@@ -403,6 +682,8 @@ pub trait FromFilelike: From<OwnedFd> {
 impl<T: From<OwnedFd>> FromFilelike for T {
     #[inline]
     fn from_filelike(owned: OwnedFilelike) -> Self {
+        #[cfg(all(unix, feature = "track-ownership"))]
+        crate::track::acquire(std::os::unix::io::AsRawFd::as_raw_fd(&owned));
         Self::from(owned)
     }
 
@@ -487,6 +768,8 @@ pub trait FromSocketlike: From<OwnedFd> {
 impl<T: From<OwnedFd>> FromSocketlike for T {
     #[inline]
     fn from_socketlike(owned: OwnedSocketlike) -> Self {
+        #[cfg(all(unix, feature = "track-ownership"))]
+        crate::track::acquire(std::os::unix::io::AsRawFd::as_raw_fd(&owned));
         Self::from(owned)
     }
 
@@ -4,6 +4,8 @@
 //! handles are distinct from socket descriptors. This file provides a minimal
 //! layer of portability over this difference.
 
+use crate::views::{FilelikeView, FilelikeViewType, SocketlikeView, SocketlikeViewType};
+use crate::{BorrowedFilelike, BorrowedSocketlike};
 #[cfg(target_os = "hermit")]
 use std::os::hermit::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 #[cfg(unix)]
@@ -253,3 +255,124 @@ impl<T: FromRawSocket> FromRawSocketlike for T {
         Self::from_raw_socket(raw)
     }
 }
+
+/// Borrow a raw filelike object for the duration of a call to `f`.
+///
+/// This is an alternative to [`BorrowedFd::borrow_raw`] and
+/// [`BorrowedHandle::borrow_raw`] for code which is handed a raw value, such
+/// as in a callback from a C library. `f` must accept a
+/// `BorrowedFilelike<'a>` for any lifetime `'a`, so the borrow can't escape
+/// the call:
+///
+/// ```rust,compile_fail
+/// use io_lifetimes::raw::with_borrowed_raw_filelike;
+/// # let raw = 0;
+///
+/// let escaped = unsafe { with_borrowed_raw_filelike(raw, |fd| fd) };
+/// ```
+///
+/// # Safety
+///
+/// `raw` must be a valid raw filelike object which remains open for the
+/// duration of the call to `f`.
+///
+/// # Example
+///
+/// ```rust
+/// use io_lifetimes::raw::{with_borrowed_raw_filelike, AsRawFilelike};
+/// use io_lifetimes::AsFilelike;
+/// use std::fs::File;
+///
+/// let file = File::open("Cargo.toml")?;
+/// let raw = file.as_raw_filelike();
+///
+/// // Safety: `file` keeps `raw` open for the duration of the call.
+/// let len = unsafe {
+///     with_borrowed_raw_filelike(raw, |fd| fd.as_filelike_view::<File>().metadata())
+/// }?
+/// .len();
+/// # let _ = len;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// [`BorrowedFd::borrow_raw`]: https://doc.rust-lang.org/stable/std/os/fd/struct.BorrowedFd.html#method.borrow_raw
+/// [`BorrowedHandle::borrow_raw`]: https://doc.rust-lang.org/stable/std/os/windows/io/struct.BorrowedHandle.html#method.borrow_raw
+#[inline]
+pub unsafe fn with_borrowed_raw_filelike<R>(
+    raw: RawFilelike,
+    f: impl for<'a> FnOnce(BorrowedFilelike<'a>) -> R,
+) -> R {
+    #[cfg(all(unix, feature = "track-ownership"))]
+    crate::track::borrow(raw);
+
+    f(BorrowedFilelike::borrow_raw(raw))
+}
+
+/// Borrow a raw socketlike object for the duration of a call to `f`.
+///
+/// This is the socketlike counterpart of [`with_borrowed_raw_filelike`].
+///
+/// # Safety
+///
+/// `raw` must be a valid raw socketlike object which remains open for the
+/// duration of the call to `f`.
+#[inline]
+pub unsafe fn with_borrowed_raw_socketlike<R>(
+    raw: RawSocketlike,
+    f: impl for<'a> FnOnce(BorrowedSocketlike<'a>) -> R,
+) -> R {
+    #[cfg(all(unix, feature = "track-ownership"))]
+    crate::track::borrow(raw);
+
+    f(BorrowedSocketlike::borrow_raw(raw))
+}
+
+/// View a raw filelike object as a `&Target` for the duration of a call to
+/// `f`.
+///
+/// This constructs a [`FilelikeView`] with [`FilelikeView::view_raw`], and
+/// passes `f` a reference to it which can't escape the call.
+///
+/// # Safety
+///
+/// `raw` must be a valid raw filelike object which remains open for the
+/// duration of the call to `f`.
+///
+/// # Example
+///
+/// ```rust
+/// use io_lifetimes::raw::{with_raw_filelike_view, AsRawFilelike};
+/// use std::fs::File;
+///
+/// let file = File::open("Cargo.toml")?;
+/// let raw = file.as_raw_filelike();
+///
+/// // Safety: `file` keeps `raw` open for the duration of the call.
+/// let metadata = unsafe { with_raw_filelike_view(raw, |file: &File| file.metadata()) }?;
+/// # let _ = metadata;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[inline]
+pub unsafe fn with_raw_filelike_view<Target: FilelikeViewType, R>(
+    raw: RawFilelike,
+    f: impl FnOnce(&Target) -> R,
+) -> R {
+    f(&FilelikeView::<Target>::view_raw(raw))
+}
+
+/// View a raw socketlike object as a `&Target` for the duration of a call to
+/// `f`.
+///
+/// This is the socketlike counterpart of [`with_raw_filelike_view`].
+///
+/// # Safety
+///
+/// `raw` must be a valid raw socketlike object which remains open for the
+/// duration of the call to `f`.
+#[inline]
+pub unsafe fn with_raw_socketlike_view<Target: SocketlikeViewType, R>(
+    raw: RawSocketlike,
+    f: impl FnOnce(&Target) -> R,
+) -> R {
+    f(&SocketlikeView::<Target>::view_raw(raw))
+}
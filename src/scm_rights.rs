@@ -0,0 +1,260 @@
+//! Passing file descriptors over Unix-domain sockets with `SCM_RIGHTS`.
+//!
+//! [`send_with_fds`] sends a message along with a set of borrowed file
+//! descriptors, and [`recv_with_fds`] receives a message along with the
+//! descriptors sent with it, as [`OwnedFd`]s, so that they're closed if the
+//! caller doesn't use them.
+//!
+//! # Example
+//!
+//! ```rust
+//! use io_lifetimes::scm_rights::{recv_with_fds, send_with_fds};
+//! use io_lifetimes::AsFilelike;
+//! use std::fs::File;
+//! use std::os::unix::net::UnixStream;
+//!
+//! let (a, b) = UnixStream::pair()?;
+//! let file = File::open("Cargo.toml")?;
+//!
+//! send_with_fds(&a, b"file", &[file.as_filelike()])?;
+//!
+//! let mut buf = [0_u8; 4];
+//! let received = recv_with_fds(&b, &mut buf, 1)?;
+//! assert_eq!(&buf[..received.len], b"file");
+//! let file = File::from(received.fds.into_iter().next().unwrap());
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::{AsSocketlike, BorrowedFd, OwnedFd};
+use libc::{c_int, c_uint, c_void};
+use std::io;
+use std::mem::{size_of, MaybeUninit};
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::ptr;
+
+/// Flags passed to `sendmsg`. Don't raise `SIGPIPE` if the peer has hung up.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const SEND_FLAGS: c_int = libc::MSG_NOSIGNAL;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+const SEND_FLAGS: c_int = 0;
+
+/// Flags passed to `recvmsg`. Set close-on-exec on received file descriptors
+/// atomically on platforms that support it.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "illumos",
+))]
+const RECV_FLAGS: c_int = libc::MSG_CMSG_CLOEXEC;
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "illumos",
+)))]
+const RECV_FLAGS: c_int = 0;
+
+/// The result of [`recv_with_fds`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Received {
+    /// The number of bytes of data received.
+    pub len: usize,
+
+    /// The file descriptors received.
+    pub fds: Vec<OwnedFd>,
+
+    /// Whether file descriptors were discarded because there wasn't room for
+    /// them, as in `MSG_CTRUNC`. Any descriptors that did fit are still in
+    /// `fds`.
+    pub fds_truncated: bool,
+
+    /// Whether data was discarded because the buffer was too small for a
+    /// datagram, as in `MSG_TRUNC`.
+    pub data_truncated: bool,
+}
+
+/// A buffer for control messages, aligned for `cmsghdr`.
+struct ControlBuffer {
+    buf: Vec<libc::cmsghdr>,
+    len: usize,
+}
+
+impl ControlBuffer {
+    /// Allocate a buffer with room for `num_fds` file descriptors.
+    fn new(num_fds: usize) -> io::Result<Self> {
+        if num_fds == 0 {
+            return Ok(Self {
+                buf: Vec::new(),
+                len: 0,
+            });
+        }
+
+        let payload = num_fds
+            .checked_mul(size_of::<c_int>())
+            .and_then(|payload| c_uint::try_from(payload).ok())
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "too many file descriptors")
+            })?;
+
+        // Safety: `CMSG_SPACE` just does arithmetic.
+        let len = unsafe { libc::CMSG_SPACE(payload) } as usize;
+
+        // Safety: `cmsghdr` is a plain C struct, for which all zeros is valid.
+        let zeroed = unsafe { MaybeUninit::<libc::cmsghdr>::zeroed().assume_init() };
+        let count = (len + size_of::<libc::cmsghdr>() - 1) / size_of::<libc::cmsghdr>();
+        let buf = vec![zeroed; count];
+
+        Ok(Self { buf, len })
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut c_void {
+        self.buf.as_mut_ptr().cast()
+    }
+}
+
+/// Construct a `msghdr` which refers to `iov` and `control`.
+fn msghdr(iov: &mut libc::iovec, control: &mut ControlBuffer) -> libc::msghdr {
+    // Safety: `msghdr` is a plain C struct, for which all zeros is valid. We
+    // zero it rather than using a struct literal because some platforms have
+    // private padding fields.
+    let mut msg = unsafe { MaybeUninit::<libc::msghdr>::zeroed().assume_init() };
+    msg.msg_iov = iov;
+    msg.msg_iovlen = 1;
+    if control.len != 0 {
+        msg.msg_control = control.as_mut_ptr();
+        msg.msg_controllen = control.len as _;
+    }
+    msg
+}
+
+/// Send `data` on `socket`, along with `fds`, using `sendmsg` with an
+/// `SCM_RIGHTS` control message.
+///
+/// `socket` must be a Unix-domain socket. The file descriptors are duplicated
+/// into the receiving process; they remain open in this process. At least
+/// one byte of data should be sent with them, as some platforms don't
+/// deliver control messages without any data.
+///
+/// On success, returns the number of bytes of `data` sent. On a stream
+/// socket, this may be less than the length of `data`, but the file
+/// descriptors are sent along with the first byte.
+pub fn send_with_fds<Socketlike: AsSocketlike>(
+    socket: &Socketlike,
+    data: &[u8],
+    fds: &[BorrowedFd<'_>],
+) -> io::Result<usize> {
+    let mut control = ControlBuffer::new(fds.len())?;
+    let mut iov = libc::iovec {
+        iov_base: data.as_ptr() as *mut c_void,
+        iov_len: data.len(),
+    };
+    let msg = msghdr(&mut iov, &mut control);
+
+    if !fds.is_empty() {
+        // Safety: `msg` refers to a control buffer with room for a header and
+        // `fds.len()` file descriptors, so `CMSG_FIRSTHDR` returns a valid
+        // pointer, and `CMSG_DATA` has room for all of `fds`.
+        unsafe {
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN((fds.len() * size_of::<c_int>()) as c_uint) as _;
+            let data = libc::CMSG_DATA(cmsg).cast::<c_int>();
+            for (i, fd) in fds.iter().enumerate() {
+                ptr::write_unaligned(data.add(i), fd.as_raw_fd());
+            }
+        }
+    }
+
+    // Safety: `msg` refers to valid buffers, which outlive this call.
+    match unsafe { libc::sendmsg(socket.as_socketlike().as_raw_fd(), &msg, SEND_FLAGS) } {
+        -1 => Err(io::Error::last_os_error()),
+        n => Ok(n as usize),
+    }
+}
+
+/// Receive data on `socket` into `buf`, along with any file descriptors sent
+/// with it, using `recvmsg`.
+///
+/// `socket` must be a Unix-domain socket. Room is reserved for at least
+/// `max_fds` file descriptors; if more were sent, the excess are closed by
+/// the OS and [`Received::fds_truncated`] is set. Every file descriptor
+/// received is returned as an [`OwnedFd`], including when the control
+/// message was truncated, so none of them are leaked.
+///
+/// The received file descriptors have close-on-exec set. On platforms
+/// without `MSG_CMSG_CLOEXEC`, this is set after they're received, so
+/// there's a window in which a concurrent `exec` in another thread could
+/// inherit them.
+pub fn recv_with_fds<Socketlike: AsSocketlike>(
+    socket: &Socketlike,
+    buf: &mut [u8],
+    max_fds: usize,
+) -> io::Result<Received> {
+    let mut control = ControlBuffer::new(max_fds)?;
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr().cast(),
+        iov_len: buf.len(),
+    };
+    let mut msg = msghdr(&mut iov, &mut control);
+
+    // Safety: `msg` refers to valid buffers, which outlive this call.
+    let len =
+        match unsafe { libc::recvmsg(socket.as_socketlike().as_raw_fd(), &mut msg, RECV_FLAGS) } {
+            -1 => return Err(io::Error::last_os_error()),
+            n => n as usize,
+        };
+
+    let mut fds = Vec::new();
+    if msg.msg_controllen != 0 {
+        // Safety: `recvmsg` succeeded, so the control buffer holds a
+        // sequence of valid control messages, and the file descriptors in
+        // `SCM_RIGHTS` messages are newly allocated and owned by us.
+        unsafe {
+            let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+            while !cmsg.is_null() {
+                if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                    let data = libc::CMSG_DATA(cmsg).cast::<c_int>();
+                    // `msg_controllen`'s type varies between platforms.
+                    #[allow(clippy::unnecessary_cast)]
+                    let control_end = msg.msg_control as usize + msg.msg_controllen as usize;
+                    // Don't trust `cmsg_len` to be at least a header's
+                    // length, or to stay within the control buffer.
+                    let payload = ((*cmsg).cmsg_len as usize)
+                        .saturating_sub(libc::CMSG_LEN(0) as usize)
+                        .min(control_end.saturating_sub(data as usize));
+                    for i in 0..payload / size_of::<c_int>() {
+                        fds.push(OwnedFd::from_raw_fd(ptr::read_unaligned(data.add(i))));
+                    }
+                }
+                cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+            }
+        }
+    }
+
+    // If we couldn't set close-on-exec atomically, set it now.
+    if RECV_FLAGS == 0 {
+        for fd in &fds {
+            // Safety: `fd` is a valid file descriptor, and `F_SETFD` takes
+            // no pointer arguments.
+            if unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+    }
+
+    Ok(Received {
+        len,
+        fds,
+        fds_truncated: msg.msg_flags & libc::MSG_CTRUNC != 0,
+        data_truncated: msg.msg_flags & libc::MSG_TRUNC != 0,
+    })
+}
@@ -0,0 +1,220 @@
+//! Reference-counted shared ownership of I/O resources.
+//!
+//! A [`Shared`] holds an owned resource such as an [`OwnedFd`] in an [`Arc`],
+//! so that it can be held from several places without duplicating it with
+//! `dup`. Duplicating a file descriptor uses up another slot in the process'
+//! file descriptor table, and `fcntl` locks are released when *any*
+//! descriptor for a file is closed, so sharing the original is often
+//! preferable. The resource is closed when the last [`Shared`] is dropped.
+//!
+//! A [`Weak`] refers to a shared resource without keeping it open.
+//!
+//! # Example
+//!
+//! ```rust
+//! use io_lifetimes::shared::SharedFilelike;
+//! use io_lifetimes::AsFilelike;
+//! use std::fs::File;
+//! use std::io::Read;
+//!
+//! let shared = SharedFilelike::new(File::open("Cargo.toml")?.into());
+//! let other = shared.clone();
+//!
+//! let mut contents = String::new();
+//! (&*other.as_filelike_view::<File>()).read_to_string(&mut contents)?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! [`Arc`]: std::sync::Arc
+
+#[cfg(any(unix, target_os = "wasi", target_os = "hermit"))]
+use crate::{AsFd, BorrowedFd, OwnedFd};
+#[cfg(windows)]
+use crate::{AsHandle, AsSocket, BorrowedHandle, BorrowedSocket, OwnedHandle, OwnedSocket};
+use crate::{OwnedFilelike, OwnedSocketlike};
+use std::fmt;
+use std::sync;
+
+/// A reference-counted owned resource.
+///
+/// This is a thin wrapper around an [`Arc`]`<T>`, which implements the
+/// borrowing traits of `T`, so that it can be used with [`AsFilelike`] and
+/// [`AsSocketlike`] and their views.
+///
+/// [`Arc`]: std::sync::Arc
+/// [`AsFilelike`]: crate::AsFilelike
+/// [`AsSocketlike`]: crate::AsSocketlike
+pub struct Shared<T> {
+    inner: sync::Arc<T>,
+}
+
+/// A non-owning reference to a [`Shared`] resource, which doesn't keep it
+/// open.
+pub struct Weak<T> {
+    inner: sync::Weak<T>,
+}
+
+/// A reference-counted [`OwnedFd`].
+#[cfg(any(unix, target_os = "wasi", target_os = "hermit"))]
+pub type SharedFd = Shared<OwnedFd>;
+
+/// A non-owning reference to a [`SharedFd`].
+#[cfg(any(unix, target_os = "wasi", target_os = "hermit"))]
+pub type WeakFd = Weak<OwnedFd>;
+
+/// A reference-counted [`OwnedHandle`].
+#[cfg(windows)]
+pub type SharedHandle = Shared<OwnedHandle>;
+
+/// A non-owning reference to a [`SharedHandle`].
+#[cfg(windows)]
+pub type WeakHandle = Weak<OwnedHandle>;
+
+/// A reference-counted [`OwnedSocket`].
+#[cfg(windows)]
+pub type SharedSocket = Shared<OwnedSocket>;
+
+/// A non-owning reference to a [`SharedSocket`].
+#[cfg(windows)]
+pub type WeakSocket = Weak<OwnedSocket>;
+
+/// A reference-counted filelike object.
+///
+/// This is a portability abstraction over Unix-like [`SharedFd`] and
+/// Windows' `SharedHandle`.
+pub type SharedFilelike = Shared<OwnedFilelike>;
+
+/// A non-owning reference to a [`SharedFilelike`].
+pub type WeakFilelike = Weak<OwnedFilelike>;
+
+/// A reference-counted socketlike object.
+///
+/// This is a portability abstraction over Unix-like [`SharedFd`] and
+/// Windows' `SharedSocket`.
+pub type SharedSocketlike = Shared<OwnedSocketlike>;
+
+/// A non-owning reference to a [`SharedSocketlike`].
+pub type WeakSocketlike = Weak<OwnedSocketlike>;
+
+impl<T> Shared<T> {
+    /// Take ownership of `owned`, so that it can be shared.
+    #[inline]
+    pub fn new(owned: T) -> Self {
+        Self {
+            inner: sync::Arc::new(owned),
+        }
+    }
+
+    /// Return the owned resource if this is the only `Shared` referring to
+    /// it. Otherwise, return `self`.
+    #[inline]
+    pub fn try_unwrap(self) -> Result<T, Self> {
+        sync::Arc::try_unwrap(self.inner).map_err(|inner| Self { inner })
+    }
+
+    /// Create a [`Weak`] reference to this resource.
+    #[inline]
+    pub fn downgrade(&self) -> Weak<T> {
+        Weak {
+            inner: sync::Arc::downgrade(&self.inner),
+        }
+    }
+
+    /// Return the number of `Shared` values referring to this resource.
+    #[inline]
+    pub fn strong_count(&self) -> usize {
+        sync::Arc::strong_count(&self.inner)
+    }
+
+    /// Test whether `self` and `other` refer to the same resource.
+    #[inline]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        sync::Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl<T> Weak<T> {
+    /// Create a `Weak` which doesn't refer to any resource.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            inner: sync::Weak::new(),
+        }
+    }
+
+    /// Return a [`Shared`] referring to the resource, if it's still open.
+    #[inline]
+    pub fn upgrade(&self) -> Option<Shared<T>> {
+        self.inner.upgrade().map(|inner| Shared { inner })
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: sync::Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> Clone for Weak<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: sync::Weak::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> Default for Weak<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<T> for Shared<T> {
+    #[inline]
+    fn from(owned: T) -> Self {
+        Self::new(owned)
+    }
+}
+
+#[cfg(any(unix, target_os = "wasi", target_os = "hermit"))]
+impl<T: AsFd> AsFd for Shared<T> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+#[cfg(windows)]
+impl<T: AsHandle> AsHandle for Shared<T> {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.inner.as_handle()
+    }
+}
+
+#[cfg(windows)]
+impl<T: AsSocket> AsSocket for Shared<T> {
+    #[inline]
+    fn as_socket(&self) -> BorrowedSocket<'_> {
+        self.inner.as_socket()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Shared<T> {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Shared").field(&self.inner).finish()
+    }
+}
+
+impl<T> fmt::Debug for Weak<T> {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(Weak)")
+    }
+}
@@ -0,0 +1,209 @@
+//! Socket activation, adopting file descriptors passed with the `LISTEN_FDS`
+//! protocol used by systemd and other service managers.
+//!
+//! A service manager which starts a process with sockets for it to listen
+//! on passes them as file descriptors starting at
+//! [`LISTEN_FDS_START`], and describes them with environment variables:
+//!
+//!  - `LISTEN_PID`: the process ID of the process they're for.
+//!  - `LISTEN_FDS`: the number of file descriptors.
+//!  - `LISTEN_FDNAMES`: optionally, a colon-separated list of their names.
+//!
+//! [`listen_fds`] validates the environment variables, takes ownership of
+//! the file descriptors, and returns them as [`ListenFd`]s, which can be
+//! converted into [`TcpListener`]s, [`UnixListener`]s, and other types
+//! implementing [`FromSocketlike`].
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use io_lifetimes::socket_activation::listen_fds;
+//! use std::net::TcpListener;
+//!
+//! for listen_fd in listen_fds()? {
+//!     if listen_fd.name() == "http" {
+//!         let listener: TcpListener = listen_fd.into_socketlike();
+//!         // ...
+//!     }
+//! }
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! [`TcpListener`]: std::net::TcpListener
+//! [`UnixListener`]: std::os::unix::net::UnixListener
+
+use crate::raw::RawFilelike;
+use crate::{AsFd, BorrowedFd, FromSocketlike, OwnedFd, OwnedSocketlike};
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::io;
+use std::os::unix::io::FromRawFd;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The first file descriptor number passed by the service manager.
+pub const LISTEN_FDS_START: RawFilelike = 3;
+
+/// The name of file descriptors which `LISTEN_FDNAMES` doesn't name.
+const DEFAULT_NAME: &str = "unknown";
+
+/// Whether [`listen_fds`] has been called.
+static ADOPTED: AtomicBool = AtomicBool::new(false);
+
+/// A file descriptor passed by the service manager, with its name.
+pub struct ListenFd {
+    fd: OwnedFd,
+    name: String,
+}
+
+impl ListenFd {
+    /// Return the name of this file descriptor, from `LISTEN_FDNAMES`, or
+    /// `"unknown"` if it wasn't named.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Convert this into a socket type such as a [`TcpListener`] or
+    /// [`UnixListener`].
+    ///
+    /// [`TcpListener`]: std::net::TcpListener
+    /// [`UnixListener`]: std::os::unix::net::UnixListener
+    #[inline]
+    pub fn into_socketlike<Socketlike: FromSocketlike>(self) -> Socketlike {
+        Socketlike::from_socketlike(self.fd)
+    }
+
+    /// Return the file descriptor and its name.
+    #[inline]
+    pub fn into_parts(self) -> (OwnedSocketlike, String) {
+        (self.fd, self.name)
+    }
+}
+
+impl AsFd for ListenFd {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl From<ListenFd> for OwnedFd {
+    #[inline]
+    fn from(listen_fd: ListenFd) -> Self {
+        listen_fd.fd
+    }
+}
+
+impl fmt::Debug for ListenFd {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ListenFd")
+            .field("fd", &self.fd)
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+/// Take ownership of the file descriptors passed by the service manager.
+///
+/// If `LISTEN_PID` is set to the current process ID, this validates
+/// `LISTEN_FDS` and `LISTEN_FDNAMES`, sets the close-on-exec flag on each of
+/// the file descriptors, and returns them in order. If `LISTEN_PID` isn't
+/// set, or is set to a different process ID, the file descriptors aren't
+/// for this process, and this returns an empty `Vec`.
+///
+/// In all cases, this removes `LISTEN_PID`, `LISTEN_FDS`, and
+/// `LISTEN_FDNAMES` from the environment, so that they aren't inherited by
+/// child processes. Since the file descriptors can only be owned once, only
+/// the first call takes ownership of them; later calls return an empty
+/// `Vec`.
+///
+/// If the environment variables are malformed, or if any of the file
+/// descriptors isn't open, this returns an [`io::ErrorKind::InvalidData`]
+/// error without taking ownership of any of them.
+///
+/// Like [`std::env::remove_var`], this should be called before starting any
+/// threads which might access the environment concurrently.
+pub fn listen_fds() -> io::Result<Vec<ListenFd>> {
+    let pid = env::var_os("LISTEN_PID");
+    let fds = env::var_os("LISTEN_FDS");
+    let names = env::var_os("LISTEN_FDNAMES");
+    env::remove_var("LISTEN_PID");
+    env::remove_var("LISTEN_FDS");
+    env::remove_var("LISTEN_FDNAMES");
+
+    if ADOPTED.swap(true, Ordering::SeqCst) {
+        return Ok(Vec::new());
+    }
+
+    let pid = match pid {
+        Some(pid) => parse::<u32>("LISTEN_PID", pid)?,
+        None => return Ok(Vec::new()),
+    };
+    if pid != std::process::id() {
+        return Ok(Vec::new());
+    }
+
+    let count = parse::<RawFilelike>("LISTEN_FDS", fds.unwrap_or_default())?;
+    if !(0..=RawFilelike::MAX - LISTEN_FDS_START).contains(&count) {
+        return Err(invalid("LISTEN_FDS is out of range"));
+    }
+    let raws = LISTEN_FDS_START..LISTEN_FDS_START + count;
+
+    // Check that all the file descriptors are open before taking ownership
+    // of any of them. This also happens before allocating anything per file
+    // descriptor, so a huge `LISTEN_FDS` fails at the first number which
+    // isn't open instead of allocating for each one.
+    for raw in raws.clone() {
+        if unsafe { libc::fcntl(raw, libc::F_GETFD) } == -1 {
+            return Err(invalid(
+                "LISTEN_FDS includes a file descriptor which isn't open",
+            ));
+        }
+    }
+
+    let mut names = match names {
+        Some(names) => {
+            let names = names
+                .into_string()
+                .map_err(|_| invalid("LISTEN_FDNAMES is not valid UTF-8"))?;
+            let names: Vec<String> = names.split(':').map(str::to_owned).collect();
+            if names.len() != raws.len() {
+                return Err(invalid(
+                    "LISTEN_FDNAMES doesn't have a name for each file descriptor",
+                ));
+            }
+            Some(names.into_iter())
+        }
+        None => None,
+    };
+
+    raws.map(|raw| {
+        // Safety: The service manager passed us ownership of the file
+        // descriptors, `ADOPTED` ensures that we only take it once, and we
+        // checked that they're open.
+        let fd = unsafe { OwnedFd::from_raw_fd(raw) };
+        if unsafe { libc::fcntl(raw, libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        let name = names
+            .as_mut()
+            .and_then(Iterator::next)
+            .unwrap_or_else(|| DEFAULT_NAME.to_owned());
+        Ok(ListenFd { fd, name })
+    })
+    .collect()
+}
+
+/// Parse the value of the environment variable `var`.
+fn parse<T: std::str::FromStr>(var: &str, value: OsString) -> io::Result<T> {
+    value
+        .to_str()
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| invalid(&format!("{} is not a valid number", var)))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
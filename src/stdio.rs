@@ -0,0 +1,175 @@
+//! Temporarily redirecting the standard I/O streams.
+//!
+//! [`StdioRedirect`] redirects file descriptor 0, 1, or 2 to another file
+//! descriptor, such as a file, and restores the original when it's dropped.
+//! This redirects output from any code which writes to the file descriptor
+//! directly, such as C libraries, as well as from Rust's [`std::io::stdout`]
+//! and [`std::io::stderr`].
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use io_lifetimes::stdio::StdioRedirect;
+//! use std::fs::File;
+//!
+//! let log = File::create("output.log")?;
+//! {
+//!     let _redirect = StdioRedirect::stdout(&log)?;
+//!     println!("this is written to output.log");
+//! }
+//! println!("this is written to the original stdout");
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::raw::RawFilelike;
+use crate::{AsFilelike, OwnedFd};
+use std::fmt;
+use std::io::{self, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd};
+
+/// One of the standard I/O streams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StdioStream {
+    /// Standard input, file descriptor 0.
+    Stdin,
+    /// Standard output, file descriptor 1.
+    Stdout,
+    /// Standard error, file descriptor 2.
+    Stderr,
+}
+
+impl StdioStream {
+    /// Return the file descriptor number of this stream.
+    #[inline]
+    pub const fn as_raw_filelike(self) -> RawFilelike {
+        match self {
+            Self::Stdin => 0,
+            Self::Stdout => 1,
+            Self::Stderr => 2,
+        }
+    }
+
+    /// Flush Rust's buffer for this stream, if it has one, so that buffered
+    /// output is written to the file descriptor it was written for.
+    fn flush(self) -> io::Result<()> {
+        match self {
+            Self::Stdin => Ok(()),
+            Self::Stdout => io::stdout().flush(),
+            Self::Stderr => io::stderr().flush(),
+        }
+    }
+}
+
+/// A guard which redirects a standard I/O stream, and restores the original
+/// when dropped.
+///
+/// The guard holds a duplicate of the stream's original file descriptor,
+/// which it duplicates back onto the stream's number when it's dropped,
+/// including when it's dropped during panic unwinding. Redirects of the same
+/// stream may be nested, and should be dropped in the reverse of the order
+/// they were created in.
+///
+/// Rust's buffer for [`std::io::stdout`] is flushed before redirecting and
+/// before restoring, so that output is written to the file descriptor that
+/// was current when it was written. Input which [`std::io::stdin`] has
+/// already buffered from the original stdin is still read before input from
+/// the redirect target.
+///
+/// Redirecting affects the whole process, including other threads.
+#[must_use]
+pub struct StdioRedirect {
+    stream: StdioStream,
+    saved: Option<OwnedFd>,
+}
+
+impl StdioRedirect {
+    /// Redirect `stream` to `target`.
+    ///
+    /// `target` is duplicated onto the stream's file descriptor number, so
+    /// it doesn't need to outlive the guard.
+    pub fn new<Filelike: AsFilelike>(stream: StdioStream, target: Filelike) -> io::Result<Self> {
+        let raw = stream.as_raw_filelike();
+
+        // Save a close-on-exec duplicate of the original, at a number above
+        // the standard I/O streams.
+        let saved = unsafe { libc::fcntl(raw, libc::F_DUPFD_CLOEXEC, 3) };
+        if saved == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        // Safety: `fcntl` returned a new file descriptor, which we own.
+        let saved = unsafe { OwnedFd::from_raw_fd(saved) };
+
+        stream.flush()?;
+        if unsafe { libc::dup2(target.as_filelike().as_raw_fd(), raw) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            stream,
+            saved: Some(saved),
+        })
+    }
+
+    /// Redirect stdin to `target`.
+    #[inline]
+    pub fn stdin<Filelike: AsFilelike>(target: Filelike) -> io::Result<Self> {
+        Self::new(StdioStream::Stdin, target)
+    }
+
+    /// Redirect stdout to `target`.
+    #[inline]
+    pub fn stdout<Filelike: AsFilelike>(target: Filelike) -> io::Result<Self> {
+        Self::new(StdioStream::Stdout, target)
+    }
+
+    /// Redirect stderr to `target`.
+    #[inline]
+    pub fn stderr<Filelike: AsFilelike>(target: Filelike) -> io::Result<Self> {
+        Self::new(StdioStream::Stderr, target)
+    }
+
+    /// Return the stream this redirects.
+    #[inline]
+    pub fn stream(&self) -> StdioStream {
+        self.stream
+    }
+
+    /// Restore the original stream, reporting any errors, which dropping
+    /// the guard ignores.
+    #[inline]
+    pub fn restore(mut self) -> io::Result<()> {
+        self.restore_saved()
+    }
+
+    fn restore_saved(&mut self) -> io::Result<()> {
+        let saved = match self.saved.take() {
+            Some(saved) => saved,
+            None => return Ok(()),
+        };
+
+        // Flush output written while redirected to the redirect target, and
+        // restore the original even if that fails.
+        let flushed = self.stream.flush();
+        if unsafe { libc::dup2(saved.as_raw_fd(), self.stream.as_raw_filelike()) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        flushed
+    }
+}
+
+impl Drop for StdioRedirect {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = self.restore_saved();
+    }
+}
+
+impl fmt::Debug for StdioRedirect {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StdioRedirect")
+            .field("stream", &self.stream)
+            .field("saved", &self.saved)
+            .finish()
+    }
+}
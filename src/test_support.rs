@@ -0,0 +1,437 @@
+//! Utilities for testing code which uses I/O resources.
+//!
+//! [`LeakGuard`] checks that a test doesn't leak file descriptors, such as by
+//! forgetting an `OwnedFd` or by converting one into a raw file descriptor
+//! and never closing it.
+//!
+//! [`check_filelike_view_type`] and [`check_socketlike_view_type`] check that
+//! a type upholds the safety contract of [`FilelikeViewType`] or
+//! [`SocketlikeViewType`], for crates which implement those traits.
+//!
+//! # Example
+//!
+//! ```rust
+//! use io_lifetimes::test_support::LeakGuard;
+//! use std::fs::File;
+//!
+//! let guard = LeakGuard::new();
+//!
+//! let file = File::open("Cargo.toml")?;
+//! drop(file);
+//!
+//! guard.assert_no_leaks();
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! [`FilelikeViewType`]: crate::views::FilelikeViewType
+//! [`SocketlikeViewType`]: crate::views::SocketlikeViewType
+
+use crate::open_fds::{open_fds, OpenFd};
+use crate::raw::RawFilelike;
+use crate::views::{FilelikeViewType, SocketlikeViewType};
+use crate::{AsFd, AsFilelike, AsSocketlike, OwnedFd};
+use std::any::type_name;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs::File;
+use std::io;
+use std::mem::take;
+use std::net::{TcpListener, TcpStream, UdpSocket};
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Targets of file descriptors which async runtimes typically open lazily
+/// and keep open for the life of the process.
+const RUNTIME_TARGETS: &[&str] = &[
+    "anon_inode:[eventpoll]",
+    "anon_inode:[eventfd]",
+    "anon_inode:[timerfd]",
+    "anon_inode:[signalfd]",
+    "anon_inode:[io_uring]",
+];
+
+/// A guard which detects file descriptors leaked while it's alive.
+///
+/// On creation, it records which file descriptors are open. When
+/// [`assert_no_leaks`] is called, or when the guard is dropped, it panics if
+/// any file descriptors are open which weren't open before, listing each one
+/// along with what it refers to.
+///
+/// File descriptors are process-wide, so anything else running concurrently,
+/// such as other tests in the same test binary, can cause false positives.
+/// Tests using `LeakGuard` should run one at a time, for example by holding
+/// a shared lock, or by using `--test-threads=1`.
+///
+/// [`assert_no_leaks`]: Self::assert_no_leaks
+#[derive(Debug)]
+pub struct LeakGuard {
+    before: BTreeMap<RawFilelike, Option<PathBuf>>,
+    allowed: Vec<String>,
+}
+
+impl LeakGuard {
+    /// Record the currently open file descriptors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the open file descriptors can't be enumerated.
+    #[allow(clippy::new_without_default)]
+    #[inline]
+    pub fn new() -> Self {
+        // The ownership tracker opens a file descriptor for its own use the
+        // first time it needs it; make sure that's not in the middle of a
+        // check.
+        #[cfg(feature = "track-ownership")]
+        crate::track::prepare();
+
+        Self {
+            before: snapshot()
+                .into_iter()
+                .map(|fd| (fd.raw_fd(), fd.target().map(Into::into)))
+                .collect(),
+            allowed: Vec::new(),
+        }
+    }
+
+    /// Don't report file descriptors whose target contains `pattern`.
+    ///
+    /// Targets are as reported by [`OpenFd::target`], such as a path, or a
+    /// description such as `anon_inode:[eventpoll]`.
+    #[inline]
+    pub fn allow_target(mut self, pattern: impl Into<String>) -> Self {
+        self.allowed.push(pattern.into());
+        self
+    }
+
+    /// Don't report file descriptors which async runtimes typically open
+    /// lazily and keep open, such as epoll instances and eventfds.
+    #[inline]
+    pub fn allow_runtime(mut self) -> Self {
+        self.allowed
+            .extend(RUNTIME_TARGETS.iter().map(|target| (*target).to_owned()));
+        self
+    }
+
+    /// Return the file descriptors which have been opened since the guard
+    /// was created, excluding those allowed by [`allow_target`] and
+    /// [`allow_runtime`].
+    ///
+    /// A file descriptor number which was open before but now refers to
+    /// something else is also included.
+    ///
+    /// [`allow_target`]: Self::allow_target
+    /// [`allow_runtime`]: Self::allow_runtime
+    pub fn leaks(&self) -> Vec<OpenFd> {
+        snapshot()
+            .into_iter()
+            .filter(|fd| match self.before.get(&fd.raw_fd()) {
+                Some(target) => target.as_deref() != fd.target(),
+                None => true,
+            })
+            .filter(|fd| !self.is_allowed(fd))
+            .collect()
+    }
+
+    /// Panic if any file descriptors have been leaked, as reported by
+    /// [`leaks`].
+    ///
+    /// [`leaks`]: Self::leaks
+    #[track_caller]
+    pub fn assert_no_leaks(&self) {
+        let leaks = self.leaks();
+        if !leaks.is_empty() {
+            panic!("{}", describe(&leaks));
+        }
+    }
+
+    fn is_allowed(&self, fd: &OpenFd) -> bool {
+        let target = match fd.target().and_then(|target| target.to_str()) {
+            Some(target) => target,
+            None => return false,
+        };
+        self.allowed
+            .iter()
+            .any(|pattern| target.contains(pattern.as_str()))
+    }
+}
+
+impl Drop for LeakGuard {
+    #[inline]
+    fn drop(&mut self) {
+        // Don't panic while panicking, which would abort.
+        if !std::thread::panicking() {
+            self.assert_no_leaks();
+        }
+    }
+}
+
+fn snapshot() -> Vec<OpenFd> {
+    open_fds().expect("failed to enumerate open file descriptors")
+}
+
+fn describe(leaks: &[OpenFd]) -> String {
+    let mut message = format!("{} file descriptor(s) leaked:", leaks.len());
+    for fd in leaks {
+        match fd.target() {
+            Some(target) => write!(message, "\n  {}: {}", fd.raw_fd(), target.display()),
+            None => write!(message, "\n  {}: {:?}", fd.raw_fd(), fd.file_type()),
+        }
+        .unwrap();
+    }
+    message
+}
+
+/// Check that `T` upholds the safety contract of [`FilelikeViewType`].
+///
+/// This creates file descriptors of several kinds, including a regular file,
+/// a directory, a character device, pipes, and sockets, and for each one
+/// that matches `T::DESCRIPTION`, it converts it into a `T` with
+/// [`FromFilelike`] and back out with [`IntoFilelike`], as views do. It then
+/// checks that:
+///
+///  - The same file descriptor number comes back.
+///  - The file descriptor is still open and refers to the same device and
+///    inode.
+///  - No new file descriptors have been left open, so `T` didn't duplicate
+///    it and return the duplicate.
+///
+/// It also creates and drops a [`FilelikeView`] of each file descriptor, and
+/// checks that the file descriptor is still open and refers to the same
+/// device and inode afterward.
+///
+/// These checks only observe the file descriptor; they can't see what `T`'s
+/// code does otherwise. They don't detect a `Drop` impl or conversion with
+/// side effects other than closing the file descriptor, such as changing
+/// its flags or writing to it, or one which closes the file descriptor and
+/// opens the same file again at the same number.
+///
+/// The last check looks at all the file descriptors in the process, so like
+/// [`LeakGuard`], this can report false positives if other threads open file
+/// descriptors concurrently.
+///
+/// # Panics
+///
+/// Panics if any check fails, or if none of the file descriptors match
+/// `T::DESCRIPTION`.
+///
+/// # Example
+///
+/// ```rust
+/// use io_lifetimes::test_support::check_filelike_view_type;
+/// use std::fs::File;
+///
+/// check_filelike_view_type::<File>();
+/// ```
+///
+/// [`FromFilelike`]: crate::FromFilelike
+/// [`IntoFilelike`]: crate::IntoFilelike
+/// [`FilelikeView`]: crate::views::FilelikeView
+#[track_caller]
+pub fn check_filelike_view_type<T: FilelikeViewType>() {
+    let target = type_name::<T>();
+    check_view_type(
+        target,
+        |fd| crate::inspect::check_filelike(fd.as_fd(), T::DESCRIPTION, target).is_ok(),
+        |owned| T::from_filelike(owned).into_filelike(),
+        |fd| drop(fd.as_filelike_view::<T>()),
+    );
+}
+
+/// Check that `T` upholds the safety contract of [`SocketlikeViewType`].
+///
+/// This is the socketlike counterpart of [`check_filelike_view_type`], and
+/// performs the same checks, with the same limitations, converting with
+/// [`FromSocketlike`] and [`IntoSocketlike`] and using only the sockets which
+/// match `T::DESCRIPTION`.
+///
+/// # Panics
+///
+/// Panics if any check fails, or if none of the file descriptors match
+/// `T::DESCRIPTION`.
+///
+/// # Example
+///
+/// ```rust
+/// use io_lifetimes::test_support::check_socketlike_view_type;
+/// use std::net::TcpStream;
+///
+/// check_socketlike_view_type::<TcpStream>();
+/// ```
+///
+/// [`FromSocketlike`]: crate::FromSocketlike
+/// [`IntoSocketlike`]: crate::IntoSocketlike
+#[track_caller]
+pub fn check_socketlike_view_type<T: SocketlikeViewType>() {
+    let target = type_name::<T>();
+    check_view_type(
+        target,
+        |fd| crate::inspect::check_socketlike(fd.as_fd(), T::DESCRIPTION, target).is_ok(),
+        |owned| T::from_socketlike(owned).into_socketlike(),
+        |fd| drop(fd.as_socketlike_view::<T>()),
+    );
+}
+
+#[track_caller]
+fn check_view_type(
+    target: &str,
+    matches: impl Fn(&OwnedFd) -> bool,
+    round_trip: impl Fn(OwnedFd) -> OwnedFd,
+    view: impl Fn(&OwnedFd),
+) {
+    let mut samples = Samples::new().expect("failed to create sample file descriptors");
+    let guard = LeakGuard::new();
+    let mut checked = 0;
+
+    for (what, fd) in take(&mut samples.fds) {
+        if !matches(&fd) {
+            samples.fds.push((what, fd));
+            continue;
+        }
+        checked += 1;
+
+        let raw = fd.as_raw_fd();
+        let id = identify(&fd).unwrap();
+
+        let fd = round_trip(fd);
+        assert_eq!(
+            fd.as_raw_fd(),
+            raw,
+            "`{}` returned a different file descriptor for a {}",
+            target,
+            what
+        );
+        assert_eq!(
+            identify(&fd).ok(),
+            Some(id),
+            "`{}` closed or replaced the file descriptor for a {}",
+            target,
+            what
+        );
+
+        view(&fd);
+        assert_eq!(
+            identify(&fd).ok(),
+            Some(id),
+            "a view of `{}` closed or replaced the file descriptor for a {}",
+            target,
+            what
+        );
+
+        samples.fds.push((what, fd));
+    }
+
+    assert!(
+        checked > 0,
+        "none of the sample file descriptors match the description of `{}`",
+        target
+    );
+
+    let leaks = guard.leaks();
+    if !leaks.is_empty() {
+        panic!(
+            "`{}` left file descriptors open; {}",
+            target,
+            describe(&leaks)
+        );
+    }
+}
+
+/// Return the device and inode of `fd`.
+fn identify(fd: &OwnedFd) -> io::Result<(u64, u64)> {
+    let metadata = fd.as_filelike_view::<File>().metadata()?;
+    Ok((metadata.dev(), metadata.ino()))
+}
+
+/// File descriptors of various kinds, along with the resources needed to
+/// keep them alive.
+struct Samples {
+    fds: Vec<(&'static str, OwnedFd)>,
+    _peers: Vec<OwnedFd>,
+    paths: Vec<PathBuf>,
+}
+
+impl Samples {
+    fn new() -> io::Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let unique = format!(
+            "io-lifetimes-check-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+
+        let mut samples = Self {
+            fds: Vec::new(),
+            _peers: Vec::new(),
+            paths: Vec::new(),
+        };
+
+        let file_path = std::env::temp_dir().join(format!("{}.file", unique));
+        samples.paths.push(file_path.clone());
+        samples.push("regular file", File::create(&file_path)?);
+        samples.push("directory", File::open(std::env::temp_dir())?);
+        samples.push("character device", File::open("/dev/null")?);
+
+        let (reader, writer) = pipe()?;
+        samples.push("pipe reader", reader);
+        samples.push("pipe writer", writer);
+
+        let (a, b) = UnixStream::pair()?;
+        samples.push("Unix-domain stream socket", a);
+        samples._peers.push(b.into());
+        let (a, b) = UnixDatagram::pair()?;
+        samples.push("Unix-domain datagram socket", a);
+        samples._peers.push(b.into());
+        let socket_path = std::env::temp_dir().join(format!("{}.sock", unique));
+        samples.paths.push(socket_path.clone());
+        samples.push(
+            "Unix-domain listening socket",
+            UnixListener::bind(&socket_path)?,
+        );
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let stream = TcpStream::connect(listener.local_addr()?)?;
+        samples._peers.push(listener.accept()?.0.into());
+        samples.push("TCP stream", stream);
+        samples.push("TCP listener", listener);
+        samples.push("UDP socket", UdpSocket::bind("127.0.0.1:0")?);
+
+        Ok(samples)
+    }
+
+    fn push(&mut self, what: &'static str, fd: impl Into<OwnedFd>) {
+        self.fds.push((what, fd.into()));
+    }
+}
+
+impl Drop for Samples {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Create a pipe, with close-on-exec set on both ends.
+fn pipe() -> io::Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0; 2];
+
+    // Safety: `fds` is a valid buffer for `pipe` to write two file
+    // descriptors to.
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // Safety: `pipe` succeeded, so we own the two new file descriptors.
+    let (reader, writer) = unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+    for fd in [&reader, &writer] {
+        // Safety: `fd` is a valid file descriptor, and `F_SETFD` takes no
+        // pointer arguments.
+        if unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok((reader, writer))
+}
@@ -0,0 +1,350 @@
+//! Debugging aid which tracks ownership of file descriptors.
+//!
+//! When the `track-ownership` feature is enabled, io-lifetimes records each
+//! file descriptor which is given to an owning type through
+//! [`FromFilelike`] or [`FromSocketlike`], along with a backtrace of where it
+//! happened, and checks for ownership bugs:
+//!
+//!  - [`ReportKind::DuplicateClaim`]: a file descriptor is given to an owning
+//!    type while another owning type already owns it, for example because
+//!    `OwnedFd::from_raw_fd` was called twice on the same number. This is
+//!    only detected on Linux and Android, and only for resources which can
+//!    be polled, such as sockets and pipes, but not regular files or
+//!    directories. See below for details.
+//!
+//!  - [`ReportKind::DoubleClose`]: closing a file descriptor with
+//!    [`CloseChecked::close`] fails because it's already closed.
+//!
+//!  - [`ReportKind::UseAfterClose`]: a view or other borrow is created from a
+//!    raw file descriptor number which isn't open.
+//!
+//!  - [`ReportKind::UnregisteredBorrow`]: a view or other borrow is created
+//!    from a raw file descriptor number which no owning type is recorded as
+//!    owning. This is only checked in strict mode, which is enabled with
+//!    [`set_strict_borrows`]. See below for details.
+//!
+//! Each problem is printed to stderr, with backtraces of the acquisition or
+//! close that conflicts with it, and saved so that it can be retrieved with
+//! [`take_reports`].
+//!
+//! io-lifetimes can't observe file descriptors being opened or closed by
+//! other code, including `OwnedFd`'s `Drop`, so its records of which file
+//! descriptors are owned may be stale. For example, a file descriptor given
+//! to a `File` which is then dropped, with the same file then opened again
+//! and given the same number, looks just like a duplicate claim in the
+//! records. So before reporting a duplicate claim, the tracker checks that
+//! the earlier owner's open file description is still open at that number,
+//! by registering each acquired file descriptor with an epoll instance.
+//! epoll forgets a registration once its open file description is closed,
+//! without keeping it open, so if the registration is still there, the
+//! earlier owner's resource is still open. Resources which epoll doesn't
+//! support, and platforms without epoll, can't be checked this way, so
+//! duplicate claims of them aren't reported.
+//!
+//! A registration can outlive the earlier owner if that owner released the
+//! file descriptor outside of io-lifetimes, such as with
+//! [`IntoRawFd::into_raw_fd`], and an acquisition after that is reported as
+//! a duplicate claim, even though the earlier owner is gone.
+//!
+//! By default, borrows are only checked for whether the file descriptor is
+//! open. Most file descriptors are opened by code which doesn't go through
+//! io-lifetimes, such as `File::open`, so the tracker has no record of them,
+//! and can't tell a borrow of a file descriptor which was closed and whose
+//! number was then reused from a borrow of a file descriptor it never saw
+//! acquired. In strict mode, every borrow of a number which isn't in the
+//! records is reported, which is useful in programs which acquire all of
+//! their file descriptors through io-lifetimes conversions, but otherwise
+//! includes false positives such as the standard streams, and file
+//! descriptors from `File::open` which haven't been converted with
+//! [`FromFilelike`].
+//!
+//! [`IntoRawFd::into_raw_fd`]: std::os::unix::io::IntoRawFd::into_raw_fd
+//! [`FromFilelike`]: crate::FromFilelike
+//! [`FromSocketlike`]: crate::FromSocketlike
+//! [`CloseChecked::close`]: crate::CloseChecked::close
+
+use crate::raw::RawFilelike;
+use std::backtrace::Backtrace;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::mem::take;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The kind of problem described by a [`Report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ReportKind {
+    /// A file descriptor was closed when it was already closed.
+    DoubleClose,
+
+    /// A file descriptor was borrowed when it wasn't open.
+    UseAfterClose,
+
+    /// A file descriptor was acquired by an owning type when another owning
+    /// type already owned it.
+    DuplicateClaim,
+
+    /// A file descriptor was borrowed when no owning type was recorded as
+    /// owning it. This is only reported in strict mode; see
+    /// [`set_strict_borrows`].
+    UnregisteredBorrow,
+}
+
+/// A problem detected by the ownership tracker.
+#[derive(Debug)]
+pub struct Report {
+    kind: ReportKind,
+    raw: RawFilelike,
+    backtrace: Backtrace,
+    previous: Option<Arc<Backtrace>>,
+}
+
+impl Report {
+    /// Return the kind of problem.
+    #[inline]
+    pub fn kind(&self) -> ReportKind {
+        self.kind
+    }
+
+    /// Return the file descriptor number.
+    #[inline]
+    pub fn raw_fd(&self) -> RawFilelike {
+        self.raw
+    }
+
+    /// Return a backtrace of where the problem was detected.
+    #[inline]
+    pub fn backtrace(&self) -> &Backtrace {
+        &self.backtrace
+    }
+
+    /// Return a backtrace of the earlier acquisition or close which conflicts
+    /// with this one, if known.
+    #[inline]
+    pub fn previous(&self) -> Option<&Backtrace> {
+        self.previous.as_deref()
+    }
+}
+
+impl fmt::Display for Report {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (what, previous) = match self.kind {
+            ReportKind::DoubleClose => ("closed twice", "previously closed at"),
+            ReportKind::UseAfterClose => ("used after being closed", "closed at"),
+            ReportKind::DuplicateClaim => ("owned twice", "previously acquired at"),
+            ReportKind::UnregisteredBorrow => ("borrowed without an owner", "closed at"),
+        };
+        write!(
+            f,
+            "io-lifetimes: file descriptor {} {}, at:\n{}",
+            self.raw, what, self.backtrace
+        )?;
+        if let Some(backtrace) = &self.previous {
+            write!(f, "\n{}:\n{}", previous, backtrace)?;
+        }
+        Ok(())
+    }
+}
+
+/// Return the problems detected so far, and clear the list.
+#[inline]
+pub fn take_reports() -> Vec<Report> {
+    take(&mut registry().reports)
+}
+
+/// Enable or disable strict mode, in which borrowing a file descriptor which
+/// no owning type is recorded as owning is reported as a
+/// [`ReportKind::UnregisteredBorrow`]. It's disabled by default.
+#[inline]
+pub fn set_strict_borrows(strict: bool) {
+    registry().strict_borrows = strict;
+}
+
+/// An owned file descriptor.
+struct Record {
+    acquired: Arc<Backtrace>,
+}
+
+struct Registry {
+    /// File descriptors which are owned by an owning type.
+    owned: BTreeMap<RawFilelike, Record>,
+
+    /// File descriptors which we've seen closed, and haven't seen acquired
+    /// since.
+    closed: BTreeMap<RawFilelike, Arc<Backtrace>>,
+
+    /// Problems detected so far.
+    reports: Vec<Report>,
+
+    /// Whether to report borrows of file descriptors which aren't in `owned`.
+    strict_borrows: bool,
+
+    /// The epoll instance with which owned file descriptors are registered,
+    /// or -1 if it hasn't been created yet.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    epoll: RawFilelike,
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    owned: BTreeMap::new(),
+    closed: BTreeMap::new(),
+    reports: Vec::new(),
+    strict_borrows: false,
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    epoll: -1,
+});
+
+fn registry() -> MutexGuard<'static, Registry> {
+    // A panic while holding the lock doesn't leave the registry
+    // inconsistent, so ignore poisoning.
+    REGISTRY.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+impl Registry {
+    fn report(&mut self, kind: ReportKind, raw: RawFilelike, previous: Option<Arc<Backtrace>>) {
+        let report = Report {
+            kind,
+            raw,
+            backtrace: Backtrace::force_capture(),
+            previous,
+        };
+        eprintln!("{}", report);
+        self.reports.push(report);
+    }
+}
+
+/// Test whether `raw` is open.
+fn is_open(raw: RawFilelike) -> bool {
+    // Safety: `F_GETFD` takes no pointer arguments, and is harmless on file
+    // descriptors which aren't open.
+    unsafe { libc::fcntl(raw, libc::F_GETFD) != -1 }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl Registry {
+    /// Return our epoll instance, creating it if needed.
+    fn epoll(&mut self) -> RawFilelike {
+        if self.epoll == -1 {
+            // Safety: `epoll_create1` takes no pointer arguments.
+            self.epoll = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+        }
+        self.epoll
+    }
+
+    /// Register `raw` with our epoll instance, returning `true` if it was
+    /// already registered, which means that the open file description it
+    /// was registered with is still open at `raw`.
+    fn register(&mut self, raw: RawFilelike) -> bool {
+        let epoll = self.epoll();
+
+        // We don't want to be notified of any events; the registration is
+        // just a marker.
+        let mut event = libc::epoll_event { events: 0, u64: 0 };
+
+        // Safety: `event` is a valid `epoll_event`, and `epoll_ctl` is
+        // harmless on file descriptors which aren't open.
+        let result = unsafe { libc::epoll_ctl(epoll, libc::EPOLL_CTL_ADD, raw, &mut event) };
+        result == -1 && io::Error::last_os_error().raw_os_error() == Some(libc::EEXIST)
+    }
+
+    /// Unregister `raw` from our epoll instance, if it's registered.
+    fn unregister(&mut self, raw: RawFilelike) {
+        if self.epoll != -1 {
+            // Safety: `epoll_ctl` ignores the event pointer for
+            // `EPOLL_CTL_DEL`, and is harmless on file descriptors which
+            // aren't open or registered.
+            unsafe {
+                libc::epoll_ctl(self.epoll, libc::EPOLL_CTL_DEL, raw, std::ptr::null_mut());
+            }
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+impl Registry {
+    /// Without epoll, we can't tell whether an earlier owner's resource is
+    /// still open, so never claim that it is.
+    fn register(&mut self, _raw: RawFilelike) -> bool {
+        false
+    }
+
+    fn unregister(&mut self, _raw: RawFilelike) {}
+}
+
+/// Prepare the tracker, so that file descriptors it opens for its own use
+/// aren't mistaken for leaks by [`LeakGuard`].
+///
+/// [`LeakGuard`]: crate::test_support::LeakGuard
+#[cfg(feature = "test-support")]
+pub(crate) fn prepare() {
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    registry().epoll();
+}
+
+/// Record that `raw` has been acquired by an owning type.
+pub(crate) fn acquire(raw: RawFilelike) {
+    let mut registry = registry();
+
+    if !is_open(raw) {
+        let closed = registry.closed.get(&raw).cloned();
+        registry.report(ReportKind::UseAfterClose, raw, closed);
+    } else if registry.register(raw) {
+        // The open file description acquired by the previous owner is still
+        // open at `raw`, so this is a duplicate.
+        if let Some(prev) = registry.owned.get(&raw) {
+            let acquired = prev.acquired.clone();
+            registry.report(ReportKind::DuplicateClaim, raw, Some(acquired));
+        }
+    }
+
+    registry.closed.remove(&raw);
+    registry.owned.insert(
+        raw,
+        Record {
+            acquired: Arc::new(Backtrace::force_capture()),
+        },
+    );
+}
+
+/// Record that `raw` has been released by an owning type, which has
+/// transferred its ownership out to an `OwnedFd`.
+pub(crate) fn release(raw: RawFilelike) {
+    let mut registry = registry();
+    registry.owned.remove(&raw);
+    registry.unregister(raw);
+}
+
+/// Record that `raw` has been closed, with `result` being the result of
+/// `close`.
+pub(crate) fn close(raw: RawFilelike, result: &io::Result<()>) {
+    let mut registry = registry();
+    registry.unregister(raw);
+
+    if matches!(result, Err(err) if err.raw_os_error() == Some(libc::EBADF)) {
+        let closed = registry.closed.get(&raw).cloned();
+        registry.report(ReportKind::DoubleClose, raw, closed);
+        return;
+    }
+
+    registry.owned.remove(&raw);
+    registry
+        .closed
+        .insert(raw, Arc::new(Backtrace::force_capture()));
+}
+
+/// Check that `raw` is open, and in strict mode that it's owned, before
+/// borrowing it.
+pub(crate) fn borrow(raw: RawFilelike) {
+    let mut registry = registry();
+    let kind = if !is_open(raw) {
+        ReportKind::UseAfterClose
+    } else if registry.strict_borrows && !registry.owned.contains_key(&raw) {
+        ReportKind::UnregisteredBorrow
+    } else {
+        return;
+    };
+    let closed = registry.closed.get(&raw).cloned();
+    registry.report(kind, raw, closed);
+}
@@ -0,0 +1,515 @@
+//! File descriptors typed by the kind of resource they refer to.
+//!
+//! [`TypedOwned<K>`] and [`TypedBorrowed<'_, K>`] are owned and borrowed file
+//! descriptors which are known to refer to a resource of the kind `K`, such
+//! as a [`Pipe`] or a [`ListeningSocket`]. Generic code can take these in
+//! place of [`OwnedFilelike`] and [`OwnedSocketlike`] to express what kind of
+//! resource it expects.
+//!
+//! They're constructed from plain file descriptors with a runtime check of
+//! the resource's kind, or infallibly from std types which always refer to a
+//! resource of a given kind, such as [`TcpListener`]. They can be converted
+//! back into plain file descriptors freely, and viewed as the types which
+//! are compatible with their kind.
+//!
+//! # Example
+//!
+//! ```rust
+//! use io_lifetimes::typed::{ListeningSocket, StreamSocket, TypedOwned};
+//! use io_lifetimes::OwnedFd;
+//! use std::net::TcpListener;
+//!
+//! let listener = TypedOwned::<ListeningSocket>::from(TcpListener::bind("127.0.0.1:0")?);
+//! let addr = listener.socketlike_view::<TcpListener>().local_addr()?;
+//!
+//! // A listening socket isn't a connected stream socket.
+//! assert!(TypedOwned::<StreamSocket>::try_from(OwnedFd::from(listener)).is_err());
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! Views are restricted to types which are compatible with the kind, so a
+//! listening socket can't be viewed as a [`TcpStream`]:
+//!
+//! ```rust,compile_fail
+//! use io_lifetimes::typed::{ListeningSocket, TypedOwned};
+//! use std::net::{TcpListener, TcpStream};
+//!
+//! let listener = TypedOwned::<ListeningSocket>::from(TcpListener::bind("127.0.0.1:0")?);
+//! let _ = listener.socketlike_view::<TcpStream>();
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! # Kinds are best-effort
+//!
+//! The kind is checked when a file descriptor is wrapped, but it isn't a
+//! safety invariant, and `unsafe` code mustn't rely on it. Std types such as
+//! [`ChildStdin`] can be constructed from any [`OwnedFd`], so the infallible
+//! conversions from them can wrap a file descriptor of another kind without
+//! any `unsafe` code. A mismatched kind only means that I/O on the file
+//! descriptor, or on its views, fails at runtime.
+//!
+//! Kinds also don't track everything about a resource. Socket kinds don't
+//! track the address family, so a [`StreamSocket`] created from a
+//! [`UnixStream`] can still be viewed as a [`TcpStream`], whose
+//! address-related methods will then fail, and [`StreamSocket`] includes
+//! stream sockets which haven't been connected yet.
+//!
+//! The restriction of views to compatible types only applies to the
+//! inherent [`TypedOwned::filelike_view`] and [`TypedOwned::socketlike_view`]
+//! methods, and their [`TypedBorrowed`] counterparts. Typed file descriptors
+//! also implement [`AsFd`], so the [`AsFilelike`] and [`AsSocketlike`]
+//! traits' methods can view them as any type:
+//!
+//! ```rust
+//! use io_lifetimes::typed::{ListeningSocket, TypedOwned};
+//! use io_lifetimes::AsSocketlike;
+//! use std::net::{TcpListener, TcpStream};
+//!
+//! let listener = TypedOwned::<ListeningSocket>::from(TcpListener::bind("127.0.0.1:0")?);
+//! let stream = listener.as_socketlike_view::<TcpStream>();
+//! assert!(stream.peer_addr().is_err());
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! [`TypedOwned<K>`]: TypedOwned
+//! [`TypedBorrowed<'_, K>`]: TypedBorrowed
+//! [`OwnedFd`]: https://doc.rust-lang.org/stable/std/os/fd/struct.OwnedFd.html
+
+use crate::access::RejectedFd;
+use crate::raw::RawFilelike;
+use crate::views::{
+    FilelikeDescription, FilelikeView, FilelikeViewType, SocketType, SocketlikeDescription,
+    SocketlikeView, SocketlikeViewType, TryViewError,
+};
+use crate::{
+    inspect, AsFd, AsFilelike, AsSocketlike, BorrowedFd, BorrowedFilelike, BorrowedSocketlike,
+    OwnedFd, OwnedFilelike, OwnedSocketlike,
+};
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::marker::PhantomData;
+use std::net::{TcpListener, TcpStream, UdpSocket};
+use std::os::unix::io::{AsRawFd, IntoRawFd};
+use std::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
+use std::process::{ChildStderr, ChildStdin, ChildStdout};
+
+/// A marker type for a kind of resource which a file descriptor can refer
+/// to, such as [`Pipe`].
+///
+/// Unlike [`kind::Kind`], which is the kind of resource that a file
+/// descriptor is found to refer to at runtime, this is used as a type
+/// parameter, to express a kind statically.
+///
+/// [`kind::Kind`]: crate::kind::Kind
+pub trait KindMarker {
+    /// The name of the kind, for error messages.
+    const NAME: &'static str;
+
+    /// Check that `fd` refers to a resource of this kind.
+    fn check(fd: BorrowedFd<'_>) -> Result<(), TryViewError>;
+}
+
+/// A marker for kinds which can be viewed as the filelike type `Target`.
+pub trait CompatibleFilelike<Target: FilelikeViewType>: KindMarker {}
+
+/// A marker for kinds which can be viewed as the socketlike type `Target`.
+pub trait CompatibleSocketlike<Target: SocketlikeViewType>: KindMarker {}
+
+macro_rules! filelike_kind {
+    ($(#[$attr:meta])* $kind:ident, $description:expr) => {
+        $(#[$attr])*
+        #[derive(Debug)]
+        pub enum $kind {}
+
+        impl KindMarker for $kind {
+            const NAME: &'static str = stringify!($kind);
+
+            #[inline]
+            fn check(fd: BorrowedFd<'_>) -> Result<(), TryViewError> {
+                inspect::check_filelike(fd, $description, Self::NAME)
+            }
+        }
+    };
+}
+
+macro_rules! socketlike_kind {
+    ($(#[$attr:meta])* $kind:ident, $socket_type:expr, $listening:expr) => {
+        $(#[$attr])*
+        #[derive(Debug)]
+        pub enum $kind {}
+
+        impl KindMarker for $kind {
+            const NAME: &'static str = stringify!($kind);
+
+            #[inline]
+            fn check(fd: BorrowedFd<'_>) -> Result<(), TryViewError> {
+                let description = SocketlikeDescription {
+                    socket_type: $socket_type,
+                    listening: Some($listening),
+                    ..SocketlikeDescription::ANY
+                };
+                inspect::check_socketlike(fd, description, Self::NAME)
+            }
+        }
+    };
+}
+
+filelike_kind!(
+    /// A pipe or FIFO.
+    Pipe,
+    FilelikeDescription::Pipe
+);
+filelike_kind!(
+    /// A regular file.
+    RegularFile,
+    FilelikeDescription::RegularFile
+);
+filelike_kind!(
+    /// A directory.
+    Directory,
+    FilelikeDescription::Directory
+);
+socketlike_kind!(
+    /// A stream socket which isn't listening for connections, such as a
+    /// connected TCP socket.
+    ///
+    /// This includes stream sockets which haven't been connected yet, and
+    /// stream sockets of any address family.
+    StreamSocket,
+    Some(SocketType::Stream),
+    false
+);
+socketlike_kind!(
+    /// A datagram socket, of any address family.
+    DatagramSocket,
+    Some(SocketType::Datagram),
+    false
+);
+socketlike_kind!(
+    /// A socket which is listening for connections, of any address family.
+    ListeningSocket,
+    None,
+    true
+);
+
+// Any kind can be viewed as an `OwnedFd`.
+impl<K: KindMarker> CompatibleFilelike<OwnedFd> for K {}
+
+impl CompatibleFilelike<File> for Pipe {}
+impl CompatibleFilelike<File> for RegularFile {}
+impl CompatibleFilelike<File> for Directory {}
+#[cfg(feature = "os_pipe")]
+impl CompatibleFilelike<os_pipe::PipeReader> for Pipe {}
+#[cfg(feature = "os_pipe")]
+impl CompatibleFilelike<os_pipe::PipeWriter> for Pipe {}
+
+impl CompatibleSocketlike<TcpStream> for StreamSocket {}
+impl CompatibleSocketlike<UnixStream> for StreamSocket {}
+impl CompatibleSocketlike<UdpSocket> for DatagramSocket {}
+impl CompatibleSocketlike<UnixDatagram> for DatagramSocket {}
+impl CompatibleSocketlike<TcpListener> for ListeningSocket {}
+impl CompatibleSocketlike<UnixListener> for ListeningSocket {}
+
+/// An owned file descriptor which refers to a resource of the kind `K`.
+#[repr(transparent)]
+pub struct TypedOwned<K: KindMarker> {
+    fd: OwnedFd,
+    _kind: PhantomData<fn() -> K>,
+}
+
+impl<K: KindMarker> TypedOwned<K> {
+    /// Check that `owned` refers to a resource of the kind `K`, and wrap it.
+    ///
+    /// On failure, the returned error holds `owned`.
+    #[inline]
+    pub fn try_from_filelike(
+        owned: OwnedFilelike,
+    ) -> Result<Self, RejectedFd<OwnedFd, TryViewError>> {
+        match K::check(owned.as_fd()) {
+            // Safety: We just checked the kind.
+            Ok(()) => Ok(unsafe { Self::from_filelike_unchecked(owned) }),
+            Err(error) => Err(RejectedFd::new(owned, error)),
+        }
+    }
+
+    /// Check that `owned` refers to a resource of the kind `K`, and wrap it.
+    ///
+    /// On failure, the returned error holds `owned`.
+    #[inline]
+    pub fn try_from_socketlike(
+        owned: OwnedSocketlike,
+    ) -> Result<Self, RejectedFd<OwnedFd, TryViewError>> {
+        Self::try_from_filelike(owned)
+    }
+
+    /// Wrap `owned` without checking that it refers to a resource of the
+    /// kind `K`.
+    ///
+    /// # Safety
+    ///
+    /// `owned` must refer to a resource of the kind `K`.
+    /// As described in the [module documentation](self), the kind is a
+    /// best-effort invariant which `unsafe` code mustn't rely on.
+    #[inline]
+    pub unsafe fn from_filelike_unchecked(owned: OwnedFilelike) -> Self {
+        Self {
+            fd: owned,
+            _kind: PhantomData,
+        }
+    }
+
+    /// Borrow the file descriptor, keeping its kind.
+    #[inline]
+    pub fn as_borrowed(&self) -> TypedBorrowed<'_, K> {
+        // Safety: `self.fd` is of the kind `K`.
+        unsafe { TypedBorrowed::from_filelike_unchecked(self.fd.as_fd()) }
+    }
+
+    /// Return a view of the file descriptor as the filelike type `Target`,
+    /// which must be compatible with the kind `K`.
+    #[inline]
+    pub fn filelike_view<Target: FilelikeViewType>(&self) -> FilelikeView<'_, Target>
+    where
+        K: CompatibleFilelike<Target>,
+    {
+        self.fd.as_filelike_view()
+    }
+
+    /// Return a view of the file descriptor as the socketlike type `Target`,
+    /// which must be compatible with the kind `K`.
+    #[inline]
+    pub fn socketlike_view<Target: SocketlikeViewType>(&self) -> SocketlikeView<'_, Target>
+    where
+        K: CompatibleSocketlike<Target>,
+    {
+        self.fd.as_socketlike_view()
+    }
+
+    /// Create a new file descriptor which refers to the same resource.
+    #[inline]
+    pub fn try_clone(&self) -> io::Result<Self> {
+        // Safety: A duplicate refers to the same resource, of the kind `K`.
+        Ok(unsafe { Self::from_filelike_unchecked(self.fd.try_clone()?) })
+    }
+}
+
+impl<K: KindMarker> TryFrom<OwnedFd> for TypedOwned<K> {
+    type Error = RejectedFd<OwnedFd, TryViewError>;
+
+    #[inline]
+    fn try_from(owned: OwnedFd) -> Result<Self, Self::Error> {
+        Self::try_from_filelike(owned)
+    }
+}
+
+impl<K: KindMarker> From<TypedOwned<K>> for OwnedFd {
+    #[inline]
+    fn from(owned: TypedOwned<K>) -> Self {
+        owned.fd
+    }
+}
+
+impl<K: KindMarker> AsFd for TypedOwned<K> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl<K: KindMarker> AsRawFd for TypedOwned<K> {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFilelike {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl<K: KindMarker> IntoRawFd for TypedOwned<K> {
+    #[inline]
+    fn into_raw_fd(self) -> RawFilelike {
+        self.fd.into_raw_fd()
+    }
+}
+
+impl<K: KindMarker> fmt::Debug for TypedOwned<K> {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedOwned")
+            .field("kind", &K::NAME)
+            .field("fd", &self.fd)
+            .finish()
+    }
+}
+
+/// A borrowed file descriptor which refers to a resource of the kind `K`.
+#[repr(transparent)]
+pub struct TypedBorrowed<'fd, K: KindMarker> {
+    fd: BorrowedFd<'fd>,
+    _kind: PhantomData<fn() -> K>,
+}
+
+impl<'fd, K: KindMarker> TypedBorrowed<'fd, K> {
+    /// Check that `borrowed` refers to a resource of the kind `K`, and wrap
+    /// it.
+    ///
+    /// On failure, the returned error holds `borrowed`.
+    #[inline]
+    pub fn try_from_filelike(
+        borrowed: BorrowedFilelike<'fd>,
+    ) -> Result<Self, RejectedFd<BorrowedFd<'fd>, TryViewError>> {
+        match K::check(borrowed) {
+            // Safety: We just checked the kind.
+            Ok(()) => Ok(unsafe { Self::from_filelike_unchecked(borrowed) }),
+            Err(error) => Err(RejectedFd::new(borrowed, error)),
+        }
+    }
+
+    /// Check that `borrowed` refers to a resource of the kind `K`, and wrap
+    /// it.
+    ///
+    /// On failure, the returned error holds `borrowed`.
+    #[inline]
+    pub fn try_from_socketlike(
+        borrowed: BorrowedSocketlike<'fd>,
+    ) -> Result<Self, RejectedFd<BorrowedFd<'fd>, TryViewError>> {
+        Self::try_from_filelike(borrowed)
+    }
+
+    /// Wrap `borrowed` without checking that it refers to a resource of the
+    /// kind `K`.
+    ///
+    /// # Safety
+    ///
+    /// `borrowed` must refer to a resource of the kind `K`.
+    /// As described in the [module documentation](self), the kind is a
+    /// best-effort invariant which `unsafe` code mustn't rely on.
+    #[inline]
+    pub const unsafe fn from_filelike_unchecked(borrowed: BorrowedFilelike<'fd>) -> Self {
+        Self {
+            fd: borrowed,
+            _kind: PhantomData,
+        }
+    }
+
+    /// Return a view of the file descriptor as the filelike type `Target`,
+    /// which must be compatible with the kind `K`.
+    #[inline]
+    pub fn filelike_view<Target: FilelikeViewType>(&self) -> FilelikeView<'fd, Target>
+    where
+        K: CompatibleFilelike<Target>,
+    {
+        // Safety: The file descriptor is borrowed for `'fd`.
+        unsafe { FilelikeView::view_raw(self.fd.as_raw_fd()) }
+    }
+
+    /// Return a view of the file descriptor as the socketlike type `Target`,
+    /// which must be compatible with the kind `K`.
+    #[inline]
+    pub fn socketlike_view<Target: SocketlikeViewType>(&self) -> SocketlikeView<'fd, Target>
+    where
+        K: CompatibleSocketlike<Target>,
+    {
+        // Safety: The file descriptor is borrowed for `'fd`.
+        unsafe { SocketlikeView::view_raw(self.fd.as_raw_fd()) }
+    }
+
+    /// Create a new owned file descriptor which refers to the same resource.
+    #[inline]
+    pub fn try_clone_to_owned(&self) -> io::Result<TypedOwned<K>> {
+        // Safety: A duplicate refers to the same resource, of the kind `K`.
+        Ok(unsafe { TypedOwned::from_filelike_unchecked(self.fd.try_clone_to_owned()?) })
+    }
+}
+
+impl<K: KindMarker> Clone for TypedBorrowed<'_, K> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K: KindMarker> Copy for TypedBorrowed<'_, K> {}
+
+impl<'fd, K: KindMarker> TryFrom<BorrowedFd<'fd>> for TypedBorrowed<'fd, K> {
+    type Error = RejectedFd<BorrowedFd<'fd>, TryViewError>;
+
+    #[inline]
+    fn try_from(borrowed: BorrowedFd<'fd>) -> Result<Self, Self::Error> {
+        Self::try_from_filelike(borrowed)
+    }
+}
+
+impl<'fd, K: KindMarker> From<TypedBorrowed<'fd, K>> for BorrowedFd<'fd> {
+    #[inline]
+    fn from(borrowed: TypedBorrowed<'fd, K>) -> Self {
+        borrowed.fd
+    }
+}
+
+impl<K: KindMarker> AsFd for TypedBorrowed<'_, K> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd
+    }
+}
+
+impl<K: KindMarker> AsRawFd for TypedBorrowed<'_, K> {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFilelike {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl<K: KindMarker> fmt::Debug for TypedBorrowed<'_, K> {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedBorrowed")
+            .field("kind", &K::NAME)
+            .field("fd", &self.fd)
+            .finish()
+    }
+}
+
+/// Implement infallible conversions from std types which always refer to a
+/// resource of a given kind.
+macro_rules! impl_from_std {
+    ($(#[$attr:meta])* $ty:ty => $kind:ident) => {
+        $(#[$attr])*
+        impl From<$ty> for TypedOwned<$kind> {
+            #[inline]
+            fn from(value: $ty) -> Self {
+                // Safety: Values of this type normally refer to resources of this
+                // kind, which is all that the kind promises.
+                unsafe { Self::from_filelike_unchecked(value.into()) }
+            }
+        }
+
+        $(#[$attr])*
+        impl<'fd> From<&'fd $ty> for TypedBorrowed<'fd, $kind> {
+            #[inline]
+            fn from(value: &'fd $ty) -> Self {
+                // Safety: Values of this type normally refer to resources of this
+                // kind, which is all that the kind promises.
+                unsafe { Self::from_filelike_unchecked(value.as_fd()) }
+            }
+        }
+    };
+}
+
+impl_from_std!(TcpStream => StreamSocket);
+impl_from_std!(UnixStream => StreamSocket);
+impl_from_std!(UdpSocket => DatagramSocket);
+impl_from_std!(UnixDatagram => DatagramSocket);
+impl_from_std!(TcpListener => ListeningSocket);
+impl_from_std!(UnixListener => ListeningSocket);
+impl_from_std!(ChildStdin => Pipe);
+impl_from_std!(ChildStdout => Pipe);
+impl_from_std!(ChildStderr => Pipe);
+impl_from_std!(
+    #[cfg(feature = "os_pipe")]
+    os_pipe::PipeReader => Pipe
+);
+impl_from_std!(
+    #[cfg(feature = "os_pipe")]
+    os_pipe::PipeWriter => Pipe
+);
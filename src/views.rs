@@ -1,9 +1,12 @@
 //! Typed views using temporary objects.
 //!
-//! This module defines the return types for [`AsFilelike::as_filelike_view`]
-//! and [`AsSocketlike::as_socketlike_view`].
+//! This module defines the return types for [`AsFilelike::as_filelike_view`],
+//! [`AsFilelike::as_filelike_view_mut`], [`AsSocketlike::as_socketlike_view`],
+//! and [`AsSocketlike::as_socketlike_view_mut`], as well as the async views
+//! returned by `as_async_filelike_view` and `as_async_socketlike_view`.
 //!
 //! [`AsSocketlike::as_socketlike_view`]: crate::AsSocketlike::as_socketlike_view
+//! [`AsSocketlike::as_socketlike_view_mut`]: crate::AsSocketlike::as_socketlike_view_mut
 
 use crate::raw::{
     AsRawFilelike, AsRawSocketlike, FromRawFilelike, FromRawSocketlike, IntoRawFilelike,
@@ -15,12 +18,15 @@ use crate::{
     AsFilelike, AsSocketlike, FromFilelike, FromSocketlike, IntoFilelike, IntoSocketlike,
     OwnedFilelike, OwnedSocketlike,
 };
+#[cfg(all(unix, feature = "close"))]
+use crate::{BorrowedFilelike, BorrowedSocketlike};
 #[cfg(windows)]
 use crate::{OwnedHandle, OwnedSocket};
 use std::fmt;
+use std::io;
 use std::marker::PhantomData;
 use std::mem::ManuallyDrop;
-use std::ops::Deref;
+use std::ops::{Deref, DerefMut};
 
 /// Declare that a type is safe to use in a [`FilelikeView`].
 ///
@@ -30,7 +36,11 @@ use std::ops::Deref;
 /// [`FromFilelike`] and consumed with [`IntoFilelike`], their `IntoFilelike`
 /// will return the same `OwnedFd` value that was passed to their
 /// `FromFilelike`.
-pub unsafe trait FilelikeViewType: FromFilelike + IntoFilelike {}
+pub unsafe trait FilelikeViewType: FromFilelike + IntoFilelike {
+    /// A description of the resources this type expects to be constructed
+    /// from. This is checked at runtime by `try_as_filelike_view`.
+    const DESCRIPTION: FilelikeDescription = FilelikeDescription::Any;
+}
 
 /// Declare that a type is safe to use in a [`SocketlikeView`].
 ///
@@ -40,10 +50,189 @@ pub unsafe trait FilelikeViewType: FromFilelike + IntoFilelike {}
 /// [`FromSocketlike`] and consumed with [`IntoSocketlike`], their
 /// `IntoSocketlike` will return the same `OwnedFd` value that was passed to
 /// their `FromSocketlike`.
-pub unsafe trait SocketlikeViewType: FromSocketlike + IntoSocketlike {}
+pub unsafe trait SocketlikeViewType: FromSocketlike + IntoSocketlike {
+    /// A description of the resources this type expects to be constructed
+    /// from. This is checked at runtime by `try_as_socketlike_view`.
+    const DESCRIPTION: SocketlikeDescription = SocketlikeDescription::ANY;
+}
 
-/// A non-owning view of a resource which dereferences to a `&Target` or
-/// `&mut Target`. These are returned by [`AsFilelike::as_filelike_view`].
+/// Declare that a type can be constructed from a filelike object and
+/// registered with an async runtime's reactor, and later deregistered and
+/// converted back.
+///
+/// This is an async counterpart to [`FilelikeViewType`], for types such as
+/// `tokio::fs::File` which can't implement `From<OwnedFd>` and
+/// `Into<OwnedFd>` because their conversions may fail or must happen within
+/// a runtime.
+pub trait AsyncFilelikeViewType: Sized {
+    /// Constructs a new instance of `Self` from the given filelike object,
+    /// registering it with the current reactor if needed. This may put the
+    /// resource into non-blocking mode.
+    fn from_filelike_registered(owned: OwnedFilelike) -> io::Result<Self>;
+
+    /// Deregisters `self` from its reactor and returns the underlying
+    /// filelike object. If this fails, the resource is closed.
+    fn into_filelike_deregistered(self) -> io::Result<OwnedFilelike>;
+}
+
+/// Declare that a type can be constructed from a socketlike object and
+/// registered with an async runtime's reactor, and later deregistered and
+/// converted back.
+///
+/// This is an async counterpart to [`SocketlikeViewType`], for types such as
+/// `tokio::net::TcpStream` which can't implement `From<OwnedFd>` and
+/// `Into<OwnedFd>` because their conversions may fail or must happen within
+/// a runtime.
+pub trait AsyncSocketlikeViewType: Sized {
+    /// Constructs a new instance of `Self` from the given socketlike object,
+    /// registering it with the current reactor if needed. This may put the
+    /// resource into non-blocking mode.
+    fn from_socketlike_registered(owned: OwnedSocketlike) -> io::Result<Self>;
+
+    /// Deregisters `self` from its reactor and returns the underlying
+    /// socketlike object. If this fails, the resource is closed.
+    fn into_socketlike_deregistered(self) -> io::Result<OwnedSocketlike>;
+}
+
+/// A description of the kind of filelike resource a [`FilelikeViewType`]
+/// expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FilelikeDescription {
+    /// Any filelike resource.
+    Any,
+
+    /// A regular file.
+    RegularFile,
+
+    /// A directory.
+    Directory,
+
+    /// A pipe or FIFO.
+    Pipe,
+
+    /// A character device.
+    CharacterDevice,
+}
+
+/// A description of the kind of socketlike resource a [`SocketlikeViewType`]
+/// expects.
+///
+/// Each field is `None` if the type accepts any value for that property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SocketlikeDescription {
+    /// The expected socket type.
+    pub socket_type: Option<SocketType>,
+
+    /// The expected socket domain.
+    pub domain: Option<SocketDomain>,
+
+    /// Whether the socket is expected to be listening for connections.
+    pub listening: Option<bool>,
+}
+
+impl SocketlikeDescription {
+    /// A description which accepts any socket.
+    pub const ANY: Self = Self {
+        socket_type: None,
+        domain: None,
+        listening: None,
+    };
+}
+
+/// A socket type, as in `SO_TYPE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SocketType {
+    /// A stream socket, as in `SOCK_STREAM`.
+    Stream,
+
+    /// A datagram socket, as in `SOCK_DGRAM`.
+    Datagram,
+
+    /// A sequenced-packet socket, as in `SOCK_SEQPACKET`.
+    SeqPacket,
+
+    /// A raw socket, as in `SOCK_RAW`.
+    Raw,
+}
+
+/// A socket domain, or address family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SocketDomain {
+    /// An IPv4 or IPv6 socket, as in `AF_INET` or `AF_INET6`.
+    Inet,
+
+    /// A Unix-domain socket, as in `AF_UNIX`.
+    Unix,
+}
+
+/// The error type returned by `try_as_filelike_view` and
+/// `try_as_socketlike_view`.
+#[cfg(all(unix, feature = "close"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TryViewError {
+    /// The resource could not be inspected.
+    Io(io::Error),
+
+    /// The resource isn't of the kind the target type expects.
+    Mismatch {
+        /// The name of the target type.
+        target: &'static str,
+
+        /// Why the resource doesn't match.
+        reason: &'static str,
+    },
+}
+
+#[cfg(all(unix, feature = "close"))]
+impl fmt::Display for TryViewError {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to inspect resource: {}", err),
+            Self::Mismatch { target, reason } => {
+                write!(f, "cannot view resource as `{}`: {}", target, reason)
+            }
+        }
+    }
+}
+
+#[cfg(all(unix, feature = "close"))]
+impl std::error::Error for TryViewError {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Mismatch { .. } => None,
+        }
+    }
+}
+
+#[cfg(all(unix, feature = "close"))]
+impl From<io::Error> for TryViewError {
+    #[inline]
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+#[cfg(all(unix, feature = "close"))]
+impl From<TryViewError> for io::Error {
+    #[inline]
+    fn from(err: TryViewError) -> Self {
+        match err {
+            TryViewError::Io(err) => err,
+            mismatch => io::Error::new(io::ErrorKind::InvalidInput, mismatch),
+        }
+    }
+}
+
+/// A non-owning view of a resource which dereferences to a `&Target`. These
+/// are returned by [`AsFilelike::as_filelike_view`].
 pub struct FilelikeView<'filelike, Target: FilelikeViewType> {
     /// The value to dereference to. This is a `ManuallyDrop` so that we can
     /// consume it in our `Drop` impl.
@@ -51,8 +240,9 @@ pub struct FilelikeView<'filelike, Target: FilelikeViewType> {
 
     /// `FilelikeViewType` implementors guarantee that their `Into<OwnedFd>`
     /// returns the same fd as their `From<OwnedFd>` gave them. This field
-    /// allows us to verify this.
-    #[cfg(debug_assertions)]
+    /// allows us to verify this. It's also used to describe the resource in
+    /// `Debug` output when the `describe` feature is enabled.
+    #[cfg(any(debug_assertions, all(unix, feature = "describe")))]
     orig: RawFilelike,
 
     /// This field exists because we don't otherwise explicitly use
@@ -60,8 +250,8 @@ pub struct FilelikeView<'filelike, Target: FilelikeViewType> {
     _phantom: PhantomData<&'filelike OwnedFilelike>,
 }
 
-/// A non-owning view of a resource which dereferences to a `&Target` or
-/// `&mut Target`. These are returned by [`AsSocketlike::as_socketlike_view`].
+/// A non-owning view of a resource which dereferences to a `&Target`. These
+/// are returned by [`AsSocketlike::as_socketlike_view`].
 pub struct SocketlikeView<'socketlike, Target: SocketlikeViewType> {
     /// The value to dereference to. This is a `ManuallyDrop` so that we can
     /// consume it in our `Drop` impl.
@@ -69,8 +259,9 @@ pub struct SocketlikeView<'socketlike, Target: SocketlikeViewType> {
 
     /// `SocketlikeViewType` implementors guarantee that their `Into<OwnedFd>`
     /// returns the same fd as their `From<OwnedFd>` gave them. This field
-    /// allows us to verify this.
-    #[cfg(debug_assertions)]
+    /// allows us to verify this. It's also used to describe the resource in
+    /// `Debug` output when the `describe` feature is enabled.
+    #[cfg(any(debug_assertions, all(unix, feature = "describe")))]
     orig: RawSocketlike,
 
     /// This field exists because we don't otherwise explicitly use
@@ -78,6 +269,95 @@ pub struct SocketlikeView<'socketlike, Target: SocketlikeViewType> {
     _phantom: PhantomData<&'socketlike OwnedSocketlike>,
 }
 
+/// A non-owning view of a resource which dereferences to a `&Target` or
+/// `&mut Target`. These are returned by [`AsFilelike::as_filelike_view_mut`].
+pub struct FilelikeViewMut<'filelike, Target: FilelikeViewType> {
+    /// The value to dereference to. This is a `ManuallyDrop` so that we can
+    /// consume it in our `Drop` impl.
+    target: ManuallyDrop<Target>,
+
+    /// `FilelikeViewType` implementors guarantee that their `Into<OwnedFd>`
+    /// returns the same fd as their `From<OwnedFd>` gave them. This field
+    /// allows us to verify this. It's also used to describe the resource in
+    /// `Debug` output when the `describe` feature is enabled.
+    #[cfg(any(debug_assertions, all(unix, feature = "describe")))]
+    orig: RawFilelike,
+
+    /// This field exists because we don't otherwise explicitly use
+    /// `'filelike`. It's a `&mut` so that the view holds an exclusive borrow
+    /// of the object it's viewing.
+    _phantom: PhantomData<&'filelike mut OwnedFilelike>,
+}
+
+/// A non-owning view of a resource which dereferences to a `&Target` or
+/// `&mut Target`. These are returned by
+/// [`AsSocketlike::as_socketlike_view_mut`].
+pub struct SocketlikeViewMut<'socketlike, Target: SocketlikeViewType> {
+    /// The value to dereference to. This is a `ManuallyDrop` so that we can
+    /// consume it in our `Drop` impl.
+    target: ManuallyDrop<Target>,
+
+    /// `SocketlikeViewType` implementors guarantee that their `Into<OwnedFd>`
+    /// returns the same fd as their `From<OwnedFd>` gave them. This field
+    /// allows us to verify this. It's also used to describe the resource in
+    /// `Debug` output when the `describe` feature is enabled.
+    #[cfg(any(debug_assertions, all(unix, feature = "describe")))]
+    orig: RawSocketlike,
+
+    /// This field exists because we don't otherwise explicitly use
+    /// `'socketlike`. It's a `&mut` so that the view holds an exclusive
+    /// borrow of the object it's viewing.
+    _phantom: PhantomData<&'socketlike mut OwnedSocketlike>,
+}
+
+/// A non-owning view of a resource as an async type, which dereferences to a
+/// `&Target` or `&mut Target`. These are returned by
+/// `AsFilelike::as_async_filelike_view`.
+///
+/// The view holds a duplicate of the resource, registered with the current
+/// async runtime's reactor, so dropping the view deregisters and closes the
+/// duplicate without closing the resource it's viewing. If the resource was
+/// in blocking mode when the view was created, it's put back into blocking
+/// mode when the view is dropped, so views of the same resource shouldn't
+/// overlap.
+#[cfg(all(unix, feature = "close"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+pub struct AsyncFilelikeView<'filelike, Target: AsyncFilelikeViewType> {
+    /// The value to dereference to. This is a `ManuallyDrop` so that we can
+    /// consume it in our `Drop` impl.
+    target: ManuallyDrop<Target>,
+
+    /// The resource being viewed, so that we can restore its blocking mode.
+    orig: BorrowedFilelike<'filelike>,
+
+    /// Whether `orig` was in blocking mode when the view was created.
+    was_blocking: bool,
+}
+
+/// A non-owning view of a resource as an async type, which dereferences to a
+/// `&Target` or `&mut Target`. These are returned by
+/// `AsSocketlike::as_async_socketlike_view`.
+///
+/// The view holds a duplicate of the resource, registered with the current
+/// async runtime's reactor, so dropping the view deregisters and closes the
+/// duplicate without closing the resource it's viewing. If the resource was
+/// in blocking mode when the view was created, it's put back into blocking
+/// mode when the view is dropped, so views of the same resource shouldn't
+/// overlap.
+#[cfg(all(unix, feature = "close"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "close"))))]
+pub struct AsyncSocketlikeView<'socketlike, Target: AsyncSocketlikeViewType> {
+    /// The value to dereference to. This is a `ManuallyDrop` so that we can
+    /// consume it in our `Drop` impl.
+    target: ManuallyDrop<Target>,
+
+    /// The resource being viewed, so that we can restore its blocking mode.
+    orig: BorrowedSocketlike<'socketlike>,
+
+    /// Whether `orig` was in blocking mode when the view was created.
+    was_blocking: bool,
+}
+
 impl<Target: FilelikeViewType> FilelikeView<'_, Target> {
     /// Construct a temporary `Target` and wrap it in a `FilelikeView` object.
     #[inline]
@@ -97,16 +377,36 @@ impl<Target: FilelikeViewType> FilelikeView<'_, Target> {
     /// the resulting view.
     #[inline]
     pub unsafe fn view_raw(raw: RawFilelike) -> Self {
+        #[cfg(all(unix, feature = "track-ownership"))]
+        crate::track::borrow(raw);
+
+        // Use `From` rather than `from_filelike`, as a view doesn't acquire
+        // ownership of the resource.
         let owned = OwnedFilelike::from_raw_filelike(raw);
         Self {
-            target: ManuallyDrop::new(Target::from_filelike(owned)),
-            #[cfg(debug_assertions)]
+            target: ManuallyDrop::new(<Target as From<OwnedFilelike>>::from(owned)),
+            #[cfg(any(debug_assertions, all(unix, feature = "describe")))]
             orig: raw,
             _phantom: PhantomData,
         }
     }
 }
 
+#[cfg(all(unix, feature = "close"))]
+impl<Target: FilelikeViewType> FilelikeView<'_, Target> {
+    /// Like `new`, but first check that `filelike` matches
+    /// `Target::DESCRIPTION`.
+    #[inline]
+    pub(crate) fn try_new<T: AsFilelike>(filelike: &T) -> Result<Self, TryViewError> {
+        crate::inspect::check_filelike(
+            filelike.as_filelike(),
+            Target::DESCRIPTION,
+            std::any::type_name::<Target>(),
+        )?;
+        Ok(Self::new(filelike))
+    }
+}
+
 impl<Target: SocketlikeViewType> SocketlikeView<'_, Target> {
     /// Construct a temporary `Target` and wrap it in a `SocketlikeView`
     /// object.
@@ -127,16 +427,194 @@ impl<Target: SocketlikeViewType> SocketlikeView<'_, Target> {
     /// outlives the resulting view.
     #[inline]
     pub unsafe fn view_raw(raw: RawSocketlike) -> Self {
+        #[cfg(all(unix, feature = "track-ownership"))]
+        crate::track::borrow(raw);
+
+        // Use `From` rather than `from_socketlike`, as a view doesn't acquire
+        // ownership of the resource.
+        let owned = OwnedSocketlike::from_raw_socketlike(raw);
+        Self {
+            target: ManuallyDrop::new(<Target as From<OwnedSocketlike>>::from(owned)),
+            #[cfg(any(debug_assertions, all(unix, feature = "describe")))]
+            orig: raw,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(all(unix, feature = "close"))]
+impl<Target: SocketlikeViewType> SocketlikeView<'_, Target> {
+    /// Like `new`, but first check that `socketlike` matches
+    /// `Target::DESCRIPTION`.
+    #[inline]
+    pub(crate) fn try_new<T: AsSocketlike>(socketlike: &T) -> Result<Self, TryViewError> {
+        crate::inspect::check_socketlike(
+            socketlike.as_socketlike(),
+            Target::DESCRIPTION,
+            std::any::type_name::<Target>(),
+        )?;
+        Ok(Self::new(socketlike))
+    }
+}
+
+impl<Target: FilelikeViewType> FilelikeViewMut<'_, Target> {
+    /// Construct a temporary `Target` and wrap it in a `FilelikeViewMut`
+    /// object.
+    #[inline]
+    pub(crate) fn new<T: AsFilelike>(filelike: &mut T) -> Self {
+        // Safety: The returned `FilelikeViewMut` is scoped to the lifetime of
+        // `filelike`, which we've exclusively borrowed here, so the view
+        // won't outlive the object it's borrowed from.
+        unsafe { Self::view_raw(filelike.as_filelike().as_raw_filelike()) }
+    }
+
+    /// Construct a temporary `Target` from raw and wrap it in a
+    /// `FilelikeViewMut` object.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid raw filelike referencing a resource that outlives
+    /// the resulting view, and which isn't otherwise used while the view
+    /// exists.
+    #[inline]
+    pub unsafe fn view_raw(raw: RawFilelike) -> Self {
+        #[cfg(all(unix, feature = "track-ownership"))]
+        crate::track::borrow(raw);
+
+        // Use `From` rather than `from_filelike`, as a view doesn't acquire
+        // ownership of the resource.
+        let owned = OwnedFilelike::from_raw_filelike(raw);
+        Self {
+            target: ManuallyDrop::new(<Target as From<OwnedFilelike>>::from(owned)),
+            #[cfg(any(debug_assertions, all(unix, feature = "describe")))]
+            orig: raw,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(all(unix, feature = "close"))]
+impl<Target: FilelikeViewType> FilelikeViewMut<'_, Target> {
+    /// Like `new`, but first check that `filelike` matches
+    /// `Target::DESCRIPTION`.
+    #[inline]
+    pub(crate) fn try_new<T: AsFilelike>(filelike: &mut T) -> Result<Self, TryViewError> {
+        crate::inspect::check_filelike(
+            filelike.as_filelike(),
+            Target::DESCRIPTION,
+            std::any::type_name::<Target>(),
+        )?;
+        Ok(Self::new(filelike))
+    }
+}
+
+impl<Target: SocketlikeViewType> SocketlikeViewMut<'_, Target> {
+    /// Construct a temporary `Target` and wrap it in a `SocketlikeViewMut`
+    /// object.
+    #[inline]
+    pub(crate) fn new<T: AsSocketlike>(socketlike: &mut T) -> Self {
+        // Safety: The returned `SocketlikeViewMut` is scoped to the lifetime
+        // of `socketlike`, which we've exclusively borrowed here, so the view
+        // won't outlive the object it's borrowed from.
+        unsafe { Self::view_raw(socketlike.as_socketlike().as_raw_socketlike()) }
+    }
+
+    /// Construct a temporary `Target` from raw and wrap it in a
+    /// `SocketlikeViewMut` object.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid raw socketlike referencing a resource that
+    /// outlives the resulting view, and which isn't otherwise used while the
+    /// view exists.
+    #[inline]
+    pub unsafe fn view_raw(raw: RawSocketlike) -> Self {
+        #[cfg(all(unix, feature = "track-ownership"))]
+        crate::track::borrow(raw);
+
+        // Use `From` rather than `from_socketlike`, as a view doesn't acquire
+        // ownership of the resource.
         let owned = OwnedSocketlike::from_raw_socketlike(raw);
         Self {
-            target: ManuallyDrop::new(Target::from_socketlike(owned)),
-            #[cfg(debug_assertions)]
+            target: ManuallyDrop::new(<Target as From<OwnedSocketlike>>::from(owned)),
+            #[cfg(any(debug_assertions, all(unix, feature = "describe")))]
             orig: raw,
             _phantom: PhantomData,
         }
     }
 }
 
+#[cfg(all(unix, feature = "close"))]
+impl<Target: SocketlikeViewType> SocketlikeViewMut<'_, Target> {
+    /// Like `new`, but first check that `socketlike` matches
+    /// `Target::DESCRIPTION`.
+    #[inline]
+    pub(crate) fn try_new<T: AsSocketlike>(socketlike: &mut T) -> Result<Self, TryViewError> {
+        crate::inspect::check_socketlike(
+            socketlike.as_socketlike(),
+            Target::DESCRIPTION,
+            std::any::type_name::<Target>(),
+        )?;
+        Ok(Self::new(socketlike))
+    }
+}
+
+#[cfg(all(unix, feature = "close"))]
+impl<'filelike, Target: AsyncFilelikeViewType> AsyncFilelikeView<'filelike, Target> {
+    /// Construct a temporary `Target` from a duplicate of `filelike`,
+    /// registered with the current reactor, and wrap it in an
+    /// `AsyncFilelikeView` object.
+    #[inline]
+    pub(crate) fn new<T: AsFilelike>(filelike: &'filelike T) -> io::Result<Self> {
+        let orig = filelike.as_filelike();
+        let was_blocking = !crate::inspect::is_nonblocking(orig)?;
+        match orig
+            .try_clone_to_owned()
+            .and_then(Target::from_filelike_registered)
+        {
+            Ok(target) => Ok(Self {
+                target: ManuallyDrop::new(target),
+                orig,
+                was_blocking,
+            }),
+            Err(err) => {
+                if was_blocking {
+                    crate::inspect::set_nonblocking(orig, false)?;
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
+#[cfg(all(unix, feature = "close"))]
+impl<'socketlike, Target: AsyncSocketlikeViewType> AsyncSocketlikeView<'socketlike, Target> {
+    /// Construct a temporary `Target` from a duplicate of `socketlike`,
+    /// registered with the current reactor, and wrap it in an
+    /// `AsyncSocketlikeView` object.
+    #[inline]
+    pub(crate) fn new<T: AsSocketlike>(socketlike: &'socketlike T) -> io::Result<Self> {
+        let orig = socketlike.as_socketlike();
+        let was_blocking = !crate::inspect::is_nonblocking(orig)?;
+        match orig
+            .try_clone_to_owned()
+            .and_then(Target::from_socketlike_registered)
+        {
+            Ok(target) => Ok(Self {
+                target: ManuallyDrop::new(target),
+                orig,
+                was_blocking,
+            }),
+            Err(err) => {
+                if was_blocking {
+                    crate::inspect::set_nonblocking(orig, false)?;
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
 impl<Target: FilelikeViewType> Deref for FilelikeView<'_, Target> {
     type Target = Target;
 
@@ -155,17 +633,84 @@ impl<Target: SocketlikeViewType> Deref for SocketlikeView<'_, Target> {
     }
 }
 
+impl<Target: FilelikeViewType> Deref for FilelikeViewMut<'_, Target> {
+    type Target = Target;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.target
+    }
+}
+
+impl<Target: FilelikeViewType> DerefMut for FilelikeViewMut<'_, Target> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.target
+    }
+}
+
+impl<Target: SocketlikeViewType> Deref for SocketlikeViewMut<'_, Target> {
+    type Target = Target;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.target
+    }
+}
+
+impl<Target: SocketlikeViewType> DerefMut for SocketlikeViewMut<'_, Target> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.target
+    }
+}
+
+#[cfg(all(unix, feature = "close"))]
+impl<Target: AsyncFilelikeViewType> Deref for AsyncFilelikeView<'_, Target> {
+    type Target = Target;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.target
+    }
+}
+
+#[cfg(all(unix, feature = "close"))]
+impl<Target: AsyncFilelikeViewType> DerefMut for AsyncFilelikeView<'_, Target> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.target
+    }
+}
+
+#[cfg(all(unix, feature = "close"))]
+impl<Target: AsyncSocketlikeViewType> Deref for AsyncSocketlikeView<'_, Target> {
+    type Target = Target;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.target
+    }
+}
+
+#[cfg(all(unix, feature = "close"))]
+impl<Target: AsyncSocketlikeViewType> DerefMut for AsyncSocketlikeView<'_, Target> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.target
+    }
+}
+
 impl<Target: FilelikeViewType> Drop for FilelikeView<'_, Target> {
     #[inline]
     fn drop(&mut self) {
-        // Use `Into*` to consume `self.target` without freeing its resource.
+        // Use `Into` to consume `self.target` without freeing its resource.
         //
         // Safety: Using `ManuallyDrop::take` requires us to ensure that
         // `self.target` is not used again. We don't use it again here, and
         // this is the `drop` function, so we know it's not used afterward.
-        let _raw = unsafe { ManuallyDrop::take(&mut self.target) }
-            .into_filelike()
-            .into_raw_filelike();
+        let owned: OwnedFilelike = unsafe { ManuallyDrop::take(&mut self.target) }.into();
+        let _raw = owned.into_raw_filelike();
 
         #[cfg(debug_assertions)]
         debug_assert_eq!(self.orig, _raw);
@@ -175,38 +720,230 @@ impl<Target: FilelikeViewType> Drop for FilelikeView<'_, Target> {
 impl<Target: SocketlikeViewType> Drop for SocketlikeView<'_, Target> {
     #[inline]
     fn drop(&mut self) {
-        // Use `Into*` to consume `self.target` without freeing its resource.
+        // Use `Into` to consume `self.target` without freeing its resource.
         //
         // Safety: Using `ManuallyDrop::take` requires us to ensure that
         // `self.target` is not used again. We don't use it again here, and
         // this is the `drop` function, so we know it's not used afterward.
-        let _raw = unsafe { ManuallyDrop::take(&mut self.target) }
-            .into_socketlike()
-            .into_raw_socketlike();
+        let owned: OwnedSocketlike = unsafe { ManuallyDrop::take(&mut self.target) }.into();
+        let _raw = owned.into_raw_socketlike();
 
         #[cfg(debug_assertions)]
         debug_assert_eq!(self.orig, _raw);
     }
 }
 
-impl<Target: FilelikeViewType> fmt::Debug for FilelikeView<'_, Target> {
+impl<Target: FilelikeViewType> Drop for FilelikeViewMut<'_, Target> {
+    #[inline]
+    fn drop(&mut self) {
+        // Use `Into` to consume `self.target` without freeing its resource.
+        //
+        // Safety: Using `ManuallyDrop::take` requires us to ensure that
+        // `self.target` is not used again. We don't use it again here, and
+        // this is the `drop` function, so we know it's not used afterward.
+        let owned: OwnedFilelike = unsafe { ManuallyDrop::take(&mut self.target) }.into();
+        let _raw = owned.into_raw_filelike();
+
+        #[cfg(debug_assertions)]
+        debug_assert_eq!(self.orig, _raw);
+    }
+}
+
+impl<Target: SocketlikeViewType> Drop for SocketlikeViewMut<'_, Target> {
+    #[inline]
+    fn drop(&mut self) {
+        // Use `Into` to consume `self.target` without freeing its resource.
+        //
+        // Safety: Using `ManuallyDrop::take` requires us to ensure that
+        // `self.target` is not used again. We don't use it again here, and
+        // this is the `drop` function, so we know it's not used afterward.
+        let owned: OwnedSocketlike = unsafe { ManuallyDrop::take(&mut self.target) }.into();
+        let _raw = owned.into_raw_socketlike();
+
+        #[cfg(debug_assertions)]
+        debug_assert_eq!(self.orig, _raw);
+    }
+}
+
+#[cfg(all(unix, feature = "close"))]
+impl<Target: AsyncFilelikeViewType> Drop for AsyncFilelikeView<'_, Target> {
+    #[inline]
+    fn drop(&mut self) {
+        // Deregister `self.target` and close our duplicate. Closing the
+        // duplicate doesn't close `self.orig`, so we don't need to check
+        // whether this succeeds.
+        //
+        // Safety: Using `ManuallyDrop::take` requires us to ensure that
+        // `self.target` is not used again. We don't use it again here, and
+        // this is the `drop` function, so we know it's not used afterward.
+        drop(unsafe { ManuallyDrop::take(&mut self.target) }.into_filelike_deregistered());
+
+        if self.was_blocking {
+            let _ = crate::inspect::set_nonblocking(self.orig, false);
+        }
+    }
+}
+
+#[cfg(all(unix, feature = "close"))]
+impl<Target: AsyncSocketlikeViewType> Drop for AsyncSocketlikeView<'_, Target> {
+    #[inline]
+    fn drop(&mut self) {
+        // Deregister `self.target` and close our duplicate. Closing the
+        // duplicate doesn't close `self.orig`, so we don't need to check
+        // whether this succeeds.
+        //
+        // Safety: Using `ManuallyDrop::take` requires us to ensure that
+        // `self.target` is not used again. We don't use it again here, and
+        // this is the `drop` function, so we know it's not used afterward.
+        drop(unsafe { ManuallyDrop::take(&mut self.target) }.into_socketlike_deregistered());
+
+        if self.was_blocking {
+            let _ = crate::inspect::set_nonblocking(self.orig, false);
+        }
+    }
+}
+
+impl<Target: FilelikeViewType + fmt::Debug> fmt::Debug for FilelikeView<'_, Target> {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("FilelikeView");
+        b.field("target", &**self);
+
+        // Safety: `self.orig` is valid for the lifetime of the view.
+        #[cfg(all(unix, feature = "describe"))]
+        b.field(
+            "fd",
+            &crate::describe::DescribeFd(&unsafe { crate::BorrowedFd::borrow_raw(self.orig) }),
+        );
+
+        b.finish()
+    }
+}
+
+impl<Target: SocketlikeViewType + fmt::Debug> fmt::Debug for SocketlikeView<'_, Target> {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("SocketlikeView");
+        b.field("target", &**self);
+
+        // Safety: `self.orig` is valid for the lifetime of the view.
+        #[cfg(all(unix, feature = "describe"))]
+        b.field(
+            "fd",
+            &crate::describe::DescribeFd(&unsafe { crate::BorrowedFd::borrow_raw(self.orig) }),
+        );
+
+        b.finish()
+    }
+}
+
+impl<Target: FilelikeViewType + fmt::Debug> fmt::Debug for FilelikeViewMut<'_, Target> {
     #[allow(clippy::missing_inline_in_public_items)]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("FilelikeView")
-            .field("target", &*self)
-            .finish()
+        let mut b = f.debug_struct("FilelikeViewMut");
+        b.field("target", &**self);
+
+        // Safety: `self.orig` is valid for the lifetime of the view.
+        #[cfg(all(unix, feature = "describe"))]
+        b.field(
+            "fd",
+            &crate::describe::DescribeFd(&unsafe { crate::BorrowedFd::borrow_raw(self.orig) }),
+        );
+
+        b.finish()
     }
 }
 
-impl<Target: SocketlikeViewType> fmt::Debug for SocketlikeView<'_, Target> {
+impl<Target: SocketlikeViewType + fmt::Debug> fmt::Debug for SocketlikeViewMut<'_, Target> {
     #[allow(clippy::missing_inline_in_public_items)]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("SocketlikeView")
-            .field("target", &*self)
-            .finish()
+        let mut b = f.debug_struct("SocketlikeViewMut");
+        b.field("target", &**self);
+
+        // Safety: `self.orig` is valid for the lifetime of the view.
+        #[cfg(all(unix, feature = "describe"))]
+        b.field(
+            "fd",
+            &crate::describe::DescribeFd(&unsafe { crate::BorrowedFd::borrow_raw(self.orig) }),
+        );
+
+        b.finish()
     }
 }
 
+#[cfg(all(unix, feature = "close"))]
+impl<Target: AsyncFilelikeViewType + fmt::Debug> fmt::Debug for AsyncFilelikeView<'_, Target> {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("AsyncFilelikeView");
+        b.field("target", &**self);
+
+        #[cfg(feature = "describe")]
+        b.field("fd", &crate::describe::DescribeFd(&self.orig));
+
+        b.finish()
+    }
+}
+
+#[cfg(all(unix, feature = "close"))]
+impl<Target: AsyncSocketlikeViewType + fmt::Debug> fmt::Debug for AsyncSocketlikeView<'_, Target> {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("AsyncSocketlikeView");
+        b.field("target", &**self);
+
+        #[cfg(feature = "describe")]
+        b.field("fd", &crate::describe::DescribeFd(&self.orig));
+
+        b.finish()
+    }
+}
+
+/// The description of a TCP stream socket.
+const TCP_STREAM: SocketlikeDescription = SocketlikeDescription {
+    socket_type: Some(SocketType::Stream),
+    domain: Some(SocketDomain::Inet),
+    listening: Some(false),
+};
+
+/// The description of a TCP listening socket.
+const TCP_LISTENER: SocketlikeDescription = SocketlikeDescription {
+    socket_type: Some(SocketType::Stream),
+    domain: Some(SocketDomain::Inet),
+    listening: Some(true),
+};
+
+/// The description of a UDP socket.
+const UDP_SOCKET: SocketlikeDescription = SocketlikeDescription {
+    socket_type: Some(SocketType::Datagram),
+    domain: Some(SocketDomain::Inet),
+    listening: None,
+};
+
+/// The description of a Unix-domain stream socket.
+#[cfg(unix)]
+const UNIX_STREAM: SocketlikeDescription = SocketlikeDescription {
+    socket_type: Some(SocketType::Stream),
+    domain: Some(SocketDomain::Unix),
+    listening: Some(false),
+};
+
+/// The description of a Unix-domain listening socket.
+#[cfg(unix)]
+const UNIX_LISTENER: SocketlikeDescription = SocketlikeDescription {
+    socket_type: Some(SocketType::Stream),
+    domain: Some(SocketDomain::Unix),
+    listening: Some(true),
+};
+
+/// The description of a Unix-domain datagram socket.
+#[cfg(unix)]
+const UNIX_DATAGRAM: SocketlikeDescription = SocketlikeDescription {
+    socket_type: Some(SocketType::Datagram),
+    domain: Some(SocketDomain::Unix),
+    listening: None,
+};
+
 #[cfg(any(unix, target_os = "wasi", target_os = "hermit"))]
 unsafe impl FilelikeViewType for OwnedFd {}
 #[cfg(windows)]
@@ -214,22 +951,38 @@ unsafe impl FilelikeViewType for OwnedHandle {}
 #[cfg(windows)]
 unsafe impl SocketlikeViewType for OwnedSocket {}
 unsafe impl FilelikeViewType for std::fs::File {}
-unsafe impl SocketlikeViewType for std::net::TcpStream {}
-unsafe impl SocketlikeViewType for std::net::TcpListener {}
-unsafe impl SocketlikeViewType for std::net::UdpSocket {}
+unsafe impl SocketlikeViewType for std::net::TcpStream {
+    const DESCRIPTION: SocketlikeDescription = TCP_STREAM;
+}
+unsafe impl SocketlikeViewType for std::net::TcpListener {
+    const DESCRIPTION: SocketlikeDescription = TCP_LISTENER;
+}
+unsafe impl SocketlikeViewType for std::net::UdpSocket {
+    const DESCRIPTION: SocketlikeDescription = UDP_SOCKET;
+}
 #[cfg(unix)]
-unsafe impl SocketlikeViewType for std::os::unix::net::UnixStream {}
+unsafe impl SocketlikeViewType for std::os::unix::net::UnixStream {
+    const DESCRIPTION: SocketlikeDescription = UNIX_STREAM;
+}
 #[cfg(unix)]
-unsafe impl SocketlikeViewType for std::os::unix::net::UnixListener {}
+unsafe impl SocketlikeViewType for std::os::unix::net::UnixListener {
+    const DESCRIPTION: SocketlikeDescription = UNIX_LISTENER;
+}
 
 #[cfg(unix)]
-unsafe impl SocketlikeViewType for std::os::unix::net::UnixDatagram {}
+unsafe impl SocketlikeViewType for std::os::unix::net::UnixDatagram {
+    const DESCRIPTION: SocketlikeDescription = UNIX_DATAGRAM;
+}
 #[cfg(not(any(target_os = "wasi", target_os = "hermit")))]
 #[cfg(feature = "os_pipe")]
-unsafe impl FilelikeViewType for os_pipe::PipeWriter {}
+unsafe impl FilelikeViewType for os_pipe::PipeWriter {
+    const DESCRIPTION: FilelikeDescription = FilelikeDescription::Pipe;
+}
 #[cfg(not(any(target_os = "wasi", target_os = "hermit")))]
 #[cfg(feature = "os_pipe")]
-unsafe impl FilelikeViewType for os_pipe::PipeReader {}
+unsafe impl FilelikeViewType for os_pipe::PipeReader {
+    const DESCRIPTION: FilelikeDescription = FilelikeDescription::Pipe;
+}
 
 #[cfg(not(any(target_os = "wasi", target_os = "hermit")))]
 #[cfg(feature = "socket2")]
@@ -237,41 +990,285 @@ unsafe impl SocketlikeViewType for socket2::Socket {}
 
 #[cfg(not(any(target_os = "wasi", target_os = "hermit")))]
 #[cfg(feature = "async-std")]
-unsafe impl SocketlikeViewType for async_std::net::TcpStream {}
-#[cfg(not(any(target_os = "wasi", target_os = "hermit")))]
-#[cfg(feature = "async-std")]
-unsafe impl SocketlikeViewType for async_std::net::TcpListener {}
+unsafe impl SocketlikeViewType for async_std::net::TcpListener {
+    const DESCRIPTION: SocketlikeDescription = TCP_LISTENER;
+}
 #[cfg(not(any(target_os = "wasi", target_os = "hermit")))]
 #[cfg(feature = "async-std")]
-unsafe impl SocketlikeViewType for async_std::net::UdpSocket {}
-#[cfg(unix)]
-#[cfg(feature = "async-std")]
-unsafe impl SocketlikeViewType for async_std::os::unix::net::UnixStream {}
+unsafe impl SocketlikeViewType for async_std::net::UdpSocket {
+    const DESCRIPTION: SocketlikeDescription = UDP_SOCKET;
+}
 #[cfg(unix)]
 #[cfg(feature = "async-std")]
-unsafe impl SocketlikeViewType for async_std::os::unix::net::UnixListener {}
+unsafe impl SocketlikeViewType for async_std::os::unix::net::UnixListener {
+    const DESCRIPTION: SocketlikeDescription = UNIX_LISTENER;
+}
 #[cfg(unix)]
 #[cfg(feature = "async-std")]
-unsafe impl SocketlikeViewType for async_std::os::unix::net::UnixDatagram {}
+unsafe impl SocketlikeViewType for async_std::os::unix::net::UnixDatagram {
+    const DESCRIPTION: SocketlikeDescription = UNIX_DATAGRAM;
+}
 
 #[cfg(feature = "mio")]
-unsafe impl SocketlikeViewType for mio::net::TcpStream {}
+unsafe impl SocketlikeViewType for mio::net::TcpStream {
+    const DESCRIPTION: SocketlikeDescription = TCP_STREAM;
+}
 #[cfg(feature = "mio")]
-unsafe impl SocketlikeViewType for mio::net::TcpListener {}
+unsafe impl SocketlikeViewType for mio::net::TcpListener {
+    const DESCRIPTION: SocketlikeDescription = TCP_LISTENER;
+}
 #[cfg(feature = "mio")]
-unsafe impl SocketlikeViewType for mio::net::UdpSocket {}
+unsafe impl SocketlikeViewType for mio::net::UdpSocket {
+    const DESCRIPTION: SocketlikeDescription = UDP_SOCKET;
+}
 #[cfg(unix)]
 #[cfg(feature = "mio")]
-unsafe impl SocketlikeViewType for mio::net::UnixDatagram {}
+unsafe impl SocketlikeViewType for mio::net::UnixDatagram {
+    const DESCRIPTION: SocketlikeDescription = UNIX_DATAGRAM;
+}
 #[cfg(unix)]
 #[cfg(feature = "mio")]
-unsafe impl SocketlikeViewType for mio::net::UnixListener {}
+unsafe impl SocketlikeViewType for mio::net::UnixListener {
+    const DESCRIPTION: SocketlikeDescription = UNIX_LISTENER;
+}
 #[cfg(unix)]
 #[cfg(feature = "mio")]
-unsafe impl SocketlikeViewType for mio::net::UnixStream {}
+unsafe impl SocketlikeViewType for mio::net::UnixStream {
+    const DESCRIPTION: SocketlikeDescription = UNIX_STREAM;
+}
 #[cfg(unix)]
 #[cfg(feature = "mio")]
-unsafe impl FilelikeViewType for mio::unix::pipe::Receiver {}
+unsafe impl FilelikeViewType for mio::unix::pipe::Receiver {
+    const DESCRIPTION: FilelikeDescription = FilelikeDescription::Pipe;
+}
 #[cfg(unix)]
 #[cfg(feature = "mio")]
-unsafe impl FilelikeViewType for mio::unix::pipe::Sender {}
+unsafe impl FilelikeViewType for mio::unix::pipe::Sender {
+    const DESCRIPTION: FilelikeDescription = FilelikeDescription::Pipe;
+}
+
+#[cfg(not(any(target_os = "wasi", target_os = "hermit")))]
+#[cfg(feature = "tokio")]
+impl AsyncSocketlikeViewType for tokio::net::TcpStream {
+    #[inline]
+    fn from_socketlike_registered(owned: OwnedSocketlike) -> io::Result<Self> {
+        let std = std::net::TcpStream::from_socketlike(owned);
+        std.set_nonblocking(true)?;
+        Self::from_std(std)
+    }
+
+    #[inline]
+    fn into_socketlike_deregistered(self) -> io::Result<OwnedSocketlike> {
+        self.into_std().map(IntoSocketlike::into_socketlike)
+    }
+}
+#[cfg(not(any(target_os = "wasi", target_os = "hermit")))]
+#[cfg(feature = "tokio")]
+impl AsyncSocketlikeViewType for tokio::net::TcpListener {
+    #[inline]
+    fn from_socketlike_registered(owned: OwnedSocketlike) -> io::Result<Self> {
+        let std = std::net::TcpListener::from_socketlike(owned);
+        std.set_nonblocking(true)?;
+        Self::from_std(std)
+    }
+
+    #[inline]
+    fn into_socketlike_deregistered(self) -> io::Result<OwnedSocketlike> {
+        self.into_std().map(IntoSocketlike::into_socketlike)
+    }
+}
+#[cfg(not(any(target_os = "wasi", target_os = "hermit")))]
+#[cfg(feature = "tokio")]
+impl AsyncSocketlikeViewType for tokio::net::UdpSocket {
+    #[inline]
+    fn from_socketlike_registered(owned: OwnedSocketlike) -> io::Result<Self> {
+        let std = std::net::UdpSocket::from_socketlike(owned);
+        std.set_nonblocking(true)?;
+        Self::from_std(std)
+    }
+
+    #[inline]
+    fn into_socketlike_deregistered(self) -> io::Result<OwnedSocketlike> {
+        self.into_std().map(IntoSocketlike::into_socketlike)
+    }
+}
+#[cfg(unix)]
+#[cfg(feature = "tokio")]
+impl AsyncSocketlikeViewType for tokio::net::UnixStream {
+    #[inline]
+    fn from_socketlike_registered(owned: OwnedSocketlike) -> io::Result<Self> {
+        let std = std::os::unix::net::UnixStream::from_socketlike(owned);
+        std.set_nonblocking(true)?;
+        Self::from_std(std)
+    }
+
+    #[inline]
+    fn into_socketlike_deregistered(self) -> io::Result<OwnedSocketlike> {
+        self.into_std().map(IntoSocketlike::into_socketlike)
+    }
+}
+#[cfg(unix)]
+#[cfg(feature = "tokio")]
+impl AsyncSocketlikeViewType for tokio::net::UnixListener {
+    #[inline]
+    fn from_socketlike_registered(owned: OwnedSocketlike) -> io::Result<Self> {
+        let std = std::os::unix::net::UnixListener::from_socketlike(owned);
+        std.set_nonblocking(true)?;
+        Self::from_std(std)
+    }
+
+    #[inline]
+    fn into_socketlike_deregistered(self) -> io::Result<OwnedSocketlike> {
+        self.into_std().map(IntoSocketlike::into_socketlike)
+    }
+}
+#[cfg(unix)]
+#[cfg(feature = "tokio")]
+impl AsyncSocketlikeViewType for tokio::net::UnixDatagram {
+    #[inline]
+    fn from_socketlike_registered(owned: OwnedSocketlike) -> io::Result<Self> {
+        let std = std::os::unix::net::UnixDatagram::from_socketlike(owned);
+        std.set_nonblocking(true)?;
+        Self::from_std(std)
+    }
+
+    #[inline]
+    fn into_socketlike_deregistered(self) -> io::Result<OwnedSocketlike> {
+        self.into_std().map(IntoSocketlike::into_socketlike)
+    }
+}
+#[cfg(not(any(target_os = "wasi", target_os = "hermit")))]
+#[cfg(feature = "tokio")]
+impl AsyncFilelikeViewType for tokio::fs::File {
+    #[inline]
+    fn from_filelike_registered(owned: OwnedFilelike) -> io::Result<Self> {
+        Ok(Self::from_std(std::fs::File::from_filelike(owned)))
+    }
+
+    #[inline]
+    fn into_filelike_deregistered(self) -> io::Result<OwnedFilelike> {
+        self.try_into_std()
+            .map(IntoFilelike::into_filelike)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "file has an operation in flight"))
+    }
+}
+// The `From<OwnedFd>` impls for the std `Child*` types used here require
+// Rust 1.74, so build.rs only enables these impls on compilers that have them.
+#[cfg(all(unix, child_stdio_from_owned_fd))]
+#[cfg(feature = "tokio")]
+impl AsyncFilelikeViewType for tokio::process::ChildStdin {
+    #[inline]
+    fn from_filelike_registered(owned: OwnedFilelike) -> io::Result<Self> {
+        Self::from_std(std::process::ChildStdin::from_filelike(owned))
+    }
+
+    #[inline]
+    fn into_filelike_deregistered(self) -> io::Result<OwnedFilelike> {
+        self.into_owned_fd()
+    }
+}
+#[cfg(all(unix, child_stdio_from_owned_fd))]
+#[cfg(feature = "tokio")]
+impl AsyncFilelikeViewType for tokio::process::ChildStdout {
+    #[inline]
+    fn from_filelike_registered(owned: OwnedFilelike) -> io::Result<Self> {
+        Self::from_std(std::process::ChildStdout::from_filelike(owned))
+    }
+
+    #[inline]
+    fn into_filelike_deregistered(self) -> io::Result<OwnedFilelike> {
+        self.into_owned_fd()
+    }
+}
+#[cfg(all(unix, child_stdio_from_owned_fd))]
+#[cfg(feature = "tokio")]
+impl AsyncFilelikeViewType for tokio::process::ChildStderr {
+    #[inline]
+    fn from_filelike_registered(owned: OwnedFilelike) -> io::Result<Self> {
+        Self::from_std(std::process::ChildStderr::from_filelike(owned))
+    }
+
+    #[inline]
+    fn into_filelike_deregistered(self) -> io::Result<OwnedFilelike> {
+        self.into_owned_fd()
+    }
+}
+
+#[cfg(not(any(target_os = "wasi", target_os = "hermit")))]
+#[cfg(feature = "async-std")]
+impl AsyncSocketlikeViewType for async_std::net::TcpStream {
+    #[inline]
+    fn from_socketlike_registered(owned: OwnedSocketlike) -> io::Result<Self> {
+        Ok(Self::from_socketlike(owned))
+    }
+
+    #[inline]
+    fn into_socketlike_deregistered(self) -> io::Result<OwnedSocketlike> {
+        std::net::TcpStream::try_from(self).map(IntoSocketlike::into_socketlike)
+    }
+}
+#[cfg(not(any(target_os = "wasi", target_os = "hermit")))]
+#[cfg(feature = "async-std")]
+impl AsyncSocketlikeViewType for async_std::net::TcpListener {
+    #[inline]
+    fn from_socketlike_registered(owned: OwnedSocketlike) -> io::Result<Self> {
+        Ok(Self::from_socketlike(owned))
+    }
+
+    #[inline]
+    fn into_socketlike_deregistered(self) -> io::Result<OwnedSocketlike> {
+        Ok(self.into_socketlike())
+    }
+}
+#[cfg(not(any(target_os = "wasi", target_os = "hermit")))]
+#[cfg(feature = "async-std")]
+impl AsyncSocketlikeViewType for async_std::net::UdpSocket {
+    #[inline]
+    fn from_socketlike_registered(owned: OwnedSocketlike) -> io::Result<Self> {
+        Ok(Self::from_socketlike(owned))
+    }
+
+    #[inline]
+    fn into_socketlike_deregistered(self) -> io::Result<OwnedSocketlike> {
+        Ok(self.into_socketlike())
+    }
+}
+#[cfg(unix)]
+#[cfg(feature = "async-std")]
+impl AsyncSocketlikeViewType for async_std::os::unix::net::UnixStream {
+    #[inline]
+    fn from_socketlike_registered(owned: OwnedSocketlike) -> io::Result<Self> {
+        Ok(Self::from_socketlike(owned))
+    }
+
+    #[inline]
+    fn into_socketlike_deregistered(self) -> io::Result<OwnedSocketlike> {
+        std::os::unix::net::UnixStream::try_from(self).map(IntoSocketlike::into_socketlike)
+    }
+}
+#[cfg(unix)]
+#[cfg(feature = "async-std")]
+impl AsyncSocketlikeViewType for async_std::os::unix::net::UnixListener {
+    #[inline]
+    fn from_socketlike_registered(owned: OwnedSocketlike) -> io::Result<Self> {
+        Ok(Self::from_socketlike(owned))
+    }
+
+    #[inline]
+    fn into_socketlike_deregistered(self) -> io::Result<OwnedSocketlike> {
+        Ok(self.into_socketlike())
+    }
+}
+#[cfg(unix)]
+#[cfg(feature = "async-std")]
+impl AsyncSocketlikeViewType for async_std::os::unix::net::UnixDatagram {
+    #[inline]
+    fn from_socketlike_registered(owned: OwnedSocketlike) -> io::Result<Self> {
+        Ok(Self::from_socketlike(owned))
+    }
+
+    #[inline]
+    fn into_socketlike_deregistered(self) -> io::Result<OwnedSocketlike> {
+        Ok(self.into_socketlike())
+    }
+}
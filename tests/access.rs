@@ -0,0 +1,124 @@
+#![cfg(all(unix, feature = "close"))]
+
+use io_lifetimes::access::{
+    BorrowedReadFd, BorrowedReadWriteFd, BorrowedWriteFd, ReadFd, ReadWriteFd, WriteFd,
+};
+use io_lifetimes::{AsFd, AsFilelike, BorrowedFd, OwnedFd};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::path::PathBuf;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "io-lifetimes-access-{}-{}",
+        std::process::id(),
+        name
+    ))
+}
+
+fn read_all(fd: BorrowedReadFd<'_>) -> String {
+    let mut s = String::new();
+    (&*fd.as_filelike_view::<File>())
+        .read_to_string(&mut s)
+        .unwrap();
+    s
+}
+
+fn write_all(fd: BorrowedWriteFd<'_>, s: &str) {
+    (&*fd.as_filelike_view::<File>())
+        .write_all(s.as_bytes())
+        .unwrap();
+}
+
+#[test]
+fn test_read_only() {
+    let owned = OwnedFd::from(File::open("Cargo.toml").unwrap());
+    let raw = owned.as_raw_fd();
+
+    // A read-only file descriptor isn't writable, and the error returns it.
+    let err = WriteFd::try_from(owned).unwrap_err();
+    assert_eq!(err.error().kind(), io::ErrorKind::InvalidInput);
+    let owned = err.into_fd();
+    assert_eq!(owned.as_raw_fd(), raw);
+    let owned = ReadWriteFd::try_from(owned).unwrap_err().into_fd();
+
+    let read = ReadFd::try_from(owned).unwrap();
+    assert_eq!(read.as_raw_fd(), raw);
+    assert!(read_all(read.as_borrowed()).contains("io-lifetimes"));
+
+    let cloned = read.try_clone().unwrap();
+    assert_ne!(cloned.as_raw_fd(), raw);
+    assert_eq!(OwnedFd::from(read).as_raw_fd(), raw);
+}
+
+#[test]
+fn test_write_only() {
+    let path = temp_path("write");
+    let file = File::create(&path).unwrap();
+
+    assert!(BorrowedReadFd::try_from(file.as_fd()).is_err());
+    let write = BorrowedWriteFd::try_from(file.as_fd()).unwrap();
+    write_all(write, "written");
+    assert_eq!(BorrowedFd::from(write).as_raw_fd(), file.as_raw_fd());
+
+    let owned = write.try_clone_to_owned().unwrap();
+    write_all(owned.as_borrowed(), " again");
+    drop(owned);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "written again");
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_read_write_narrowing() {
+    let path = temp_path("read-write");
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .unwrap();
+
+    // A read-write file descriptor can be narrowed, and the narrowed file
+    // descriptors are still readable and writable.
+    let both = BorrowedReadWriteFd::try_from_filelike(file.as_fd()).unwrap();
+    write_all(both.into(), "both");
+    let read_only = File::open(&path).unwrap();
+    assert_eq!(
+        read_all(BorrowedReadFd::try_from(read_only.as_fd()).unwrap()),
+        "both"
+    );
+
+    let owned = ReadWriteFd::try_from(OwnedFd::from(file)).unwrap();
+    let read: ReadFd = owned.try_clone().unwrap().into();
+    let write: WriteFd = owned.into();
+    assert!(WriteFd::try_from(OwnedFd::from(read)).is_ok());
+    assert!(ReadFd::try_from(OwnedFd::from(write)).is_ok());
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_o_path() {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    // `O_PATH` file descriptors can't be read from or written to.
+    let file = OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_PATH)
+        .open("Cargo.toml")
+        .unwrap();
+    assert!(BorrowedReadFd::try_from(file.as_fd()).is_err());
+    assert!(BorrowedWriteFd::try_from(file.as_fd()).is_err());
+}
+
+#[test]
+fn test_error_conversion() {
+    let err = WriteFd::try_from(OwnedFd::from(File::open("Cargo.toml").unwrap())).unwrap_err();
+    assert!(err.to_string().contains("not open for writing"));
+    let err: io::Error = err.into();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
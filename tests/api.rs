@@ -143,3 +143,36 @@ fn test_as() {
     let borrow_ref_reborrow: BorrowedFilelike = ref_reborrow.as_filelike();
     let _ref_borrow_ref_reborrow: &BorrowedFilelike = &borrow_ref_reborrow;
 }
+
+#[test]
+fn test_view_mut() {
+    let mut file = std::fs::File::open("Cargo.toml").unwrap();
+    let mut contents = String::new();
+    file.as_filelike_view_mut::<std::fs::File>()
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert!(contents.contains("io-lifetimes"));
+
+    let mut view = file.as_filelike_view_mut::<std::fs::File>();
+    let _ = dbg!(&view);
+    let _ = view.write(b"").is_ok();
+    drop(view);
+
+    let mut socket = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = socket.local_addr().unwrap();
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    let mut view = stream.as_socketlike_view_mut::<std::net::TcpStream>();
+    view.write_all(b"hello").unwrap();
+    drop(view);
+
+    let (mut accepted, _) = socket
+        .as_socketlike_view_mut::<std::net::TcpListener>()
+        .accept()
+        .unwrap();
+    let mut buf = [0_u8; 5];
+    accepted
+        .as_socketlike_view_mut::<std::net::TcpStream>()
+        .read_exact(&mut buf)
+        .unwrap();
+    assert_eq!(&buf, b"hello");
+}
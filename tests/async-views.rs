@@ -0,0 +1,85 @@
+#![cfg(all(unix, feature = "close"))]
+#![cfg(any(feature = "tokio", feature = "async-std"))]
+
+use io_lifetimes::AsSocketlike;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::os::unix::net::UnixStream;
+
+fn is_nonblocking(stream: &impl AsSocketlike) -> bool {
+    use io_lifetimes::raw::AsRawSocketlike;
+    let flags = unsafe { libc::fcntl(stream.as_socketlike().as_raw_socketlike(), libc::F_GETFL) };
+    assert_ne!(flags, -1);
+    flags & libc::O_NONBLOCK != 0
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn test_tokio_async_views() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut client = TcpStream::connect(addr).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let mut view = server
+                .as_async_socketlike_view::<tokio::net::TcpStream>()
+                .unwrap();
+            assert!(is_nonblocking(&server));
+            view.write_all(b"hello").await.unwrap();
+
+            client.write_all(b"world").unwrap();
+            let mut buf = [0_u8; 5];
+            view.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"world");
+        });
+
+    // Dropping the view restored blocking mode and didn't close `server`.
+    assert!(!is_nonblocking(&server));
+    let mut buf = [0_u8; 5];
+    client.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+    server.write_all(b"again").unwrap();
+    client.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"again");
+}
+
+#[cfg(feature = "async-std")]
+#[test]
+fn test_async_std_async_views() {
+    use async_std::io::{ReadExt, WriteExt};
+
+    let (mut a, mut b) = UnixStream::pair().unwrap();
+
+    async_std::task::block_on(async {
+        let mut view = a
+            .as_async_socketlike_view::<async_std::os::unix::net::UnixStream>()
+            .unwrap();
+        view.write_all(b"hello").await.unwrap();
+
+        b.write_all(b"world").unwrap();
+        let mut buf = [0_u8; 5];
+        view.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"world");
+    });
+
+    assert!(!is_nonblocking(&a));
+    let mut buf = [0_u8; 5];
+    b.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+    a.write_all(b"again").unwrap();
+    b.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"again");
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let view = listener
+        .as_async_socketlike_view::<async_std::net::TcpListener>()
+        .unwrap();
+    assert_eq!(view.local_addr().unwrap(), listener.local_addr().unwrap());
+}
@@ -0,0 +1,149 @@
+#![cfg(all(unix, feature = "close"))]
+
+use io_lifetimes::c_api::{NullableBorrowedFd, NullableOwnedFd, HEADER};
+use io_lifetimes::{AsFd, BorrowedFd, OwnedFd};
+use std::ffi::{c_char, c_int, c_void};
+use std::fs::File;
+use std::mem::{align_of, size_of, transmute, transmute_copy};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// The C functions in `tests/c/c-api.c`.
+struct Lib {
+    open: unsafe extern "C" fn(*const c_char) -> NullableOwnedFd,
+    dup: unsafe extern "C" fn(BorrowedFd<'_>) -> NullableOwnedFd,
+    close: unsafe extern "C" fn(OwnedFd) -> c_int,
+    number: unsafe extern "C" fn(BorrowedFd<'_>) -> c_int,
+    is_none: unsafe extern "C" fn(NullableBorrowedFd<'_>) -> c_int,
+    pass: unsafe extern "C" fn(NullableOwnedFd) -> NullableOwnedFd,
+}
+
+/// Compile `tests/c/c-api.c` into a shared library with the system C
+/// compiler, and load it.
+fn lib() -> &'static Lib {
+    static LIB: OnceLock<Lib> = OnceLock::new();
+    LIB.get_or_init(|| {
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join("libc-api-test.so");
+        let cc = std::env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+        let status = Command::new(cc)
+            .arg(if cfg!(target_os = "macos") {
+                "-dynamiclib"
+            } else {
+                "-shared"
+            })
+            .args(["-fPIC", "-Wall", "-Werror", "-I"])
+            .arg(manifest.join("include"))
+            .arg(manifest.join("tests/c/c-api.c"))
+            .arg("-o")
+            .arg(&out)
+            .status()
+            .expect("failed to run the C compiler");
+        assert!(status.success(), "failed to compile tests/c/c-api.c");
+
+        let mut path = out.as_os_str().as_bytes().to_vec();
+        path.push(0);
+        let handle = unsafe { libc::dlopen(path.as_ptr().cast(), libc::RTLD_NOW) };
+        assert!(!handle.is_null(), "failed to load {}", out.display());
+
+        let sym = |name: &[u8]| -> *mut c_void {
+            let sym = unsafe { libc::dlsym(handle, name.as_ptr().cast()) };
+            assert!(!sym.is_null(), "missing symbol {:?}", name);
+            sym
+        };
+
+        unsafe {
+            Lib {
+                open: transmute_copy(&sym(b"c_open\0")),
+                dup: transmute_copy(&sym(b"c_dup\0")),
+                close: transmute_copy(&sym(b"c_close\0")),
+                number: transmute_copy(&sym(b"c_number\0")),
+                is_none: transmute_copy(&sym(b"c_is_none\0")),
+                pass: transmute_copy(&sym(b"c_pass\0")),
+            }
+        }
+    })
+}
+
+#[test]
+fn test_layouts() {
+    assert_eq!(size_of::<NullableOwnedFd>(), size_of::<c_int>());
+    assert_eq!(align_of::<NullableOwnedFd>(), align_of::<c_int>());
+    assert_eq!(size_of::<NullableBorrowedFd<'_>>(), size_of::<c_int>());
+    assert_eq!(align_of::<NullableBorrowedFd<'_>>(), align_of::<c_int>());
+
+    assert_eq!(
+        unsafe { transmute::<NullableOwnedFd, c_int>(NullableOwnedFd::NONE) },
+        -1
+    );
+    assert_eq!(
+        unsafe { transmute::<NullableBorrowedFd<'_>, c_int>(NullableBorrowedFd::NONE) },
+        -1
+    );
+
+    let file = File::open("Cargo.toml").unwrap();
+    let nullable = NullableBorrowedFd::from(file.as_fd());
+    assert_eq!(
+        unsafe { transmute::<NullableBorrowedFd<'_>, c_int>(nullable) },
+        file.as_raw_fd()
+    );
+}
+
+#[test]
+fn test_header() {
+    for typedef in [
+        "typedef int owned_fd;",
+        "typedef int borrowed_fd;",
+        "typedef int nullable_owned_fd;",
+        "typedef int nullable_borrowed_fd;",
+    ] {
+        assert!(HEADER.contains(typedef), "{}", typedef);
+    }
+}
+
+#[test]
+fn test_owned_from_c() {
+    let lib = lib();
+
+    let fd = unsafe { (lib.open)(b"Cargo.toml\0".as_ptr().cast()) }
+        .into_option()
+        .unwrap();
+    assert!(File::from(fd).metadata().unwrap().is_file());
+
+    let none = unsafe { (lib.open)(b"/no/such/file\0".as_ptr().cast()) };
+    assert!(none.is_none());
+}
+
+#[test]
+fn test_borrowed_to_c() {
+    let lib = lib();
+    let file = File::open("Cargo.toml").unwrap();
+
+    assert_eq!(unsafe { (lib.number)(file.as_fd()) }, file.as_raw_fd());
+
+    let dup = unsafe { (lib.dup)(file.as_fd()) }.into_option().unwrap();
+    assert_ne!(dup.as_raw_fd(), file.as_raw_fd());
+
+    assert_eq!(unsafe { (lib.is_none)(NullableBorrowedFd::NONE) }, 1);
+    assert_eq!(unsafe { (lib.is_none)(file.as_fd().into()) }, 0);
+    assert_eq!(unsafe { (lib.is_none)(dup.as_fd().into()) }, 0);
+}
+
+#[test]
+fn test_owned_to_c() {
+    let lib = lib();
+    let file = File::open("Cargo.toml").unwrap();
+    let raw = file.as_raw_fd();
+
+    // Pass ownership through C and back.
+    let back = unsafe { (lib.pass)(OwnedFd::from(file).into()) };
+    assert_eq!(back.as_borrowed().into_option().unwrap().as_raw_fd(), raw);
+    assert!(unsafe { (lib.pass)(NullableOwnedFd::NONE) }.is_none());
+
+    // Pass ownership to C, which closes it.
+    let fd = back.into_option().unwrap();
+    assert_eq!(unsafe { (lib.close)(fd) }, 0);
+}
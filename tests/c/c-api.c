@@ -0,0 +1,31 @@
+/* C functions used by tests/c-api.rs to test passing file descriptors
+ * across the FFI boundary using the types in io_lifetimes.h. */
+
+#include <fcntl.h>
+#include <unistd.h>
+
+#include "io_lifetimes.h"
+
+nullable_owned_fd c_open(const char *path) {
+    return open(path, O_RDONLY | O_CLOEXEC);
+}
+
+nullable_owned_fd c_dup(borrowed_fd fd) {
+    return fcntl(fd, F_DUPFD_CLOEXEC, 0);
+}
+
+int c_close(owned_fd fd) {
+    return close(fd);
+}
+
+int c_number(borrowed_fd fd) {
+    return fd;
+}
+
+int c_is_none(nullable_borrowed_fd fd) {
+    return fd == IO_LIFETIMES_NO_FD;
+}
+
+nullable_owned_fd c_pass(nullable_owned_fd fd) {
+    return fd;
+}
@@ -0,0 +1,164 @@
+#![cfg(all(unix, feature = "close"))]
+
+use io_lifetimes::channel::{
+    fd_channel, message_channel, FdReceiver, FdSender, MessageReceiver, MessageSender,
+};
+use io_lifetimes::AsFilelike;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::os::unix::net::{UnixDatagram, UnixStream};
+
+#[test]
+fn test_message_channel() {
+    let (sender, receiver) = message_channel().unwrap();
+    let (a, b) = UnixStream::pair().unwrap();
+
+    sender.send(b"first", &[a.as_filelike()]).unwrap();
+    sender.send(b"", &[]).unwrap();
+    drop(sender);
+
+    let message = receiver.recv().unwrap().unwrap();
+    assert_eq!(message.data, b"first");
+    assert_eq!(message.fds.len(), 1);
+    let mut new_a = UnixStream::from(message.fds.into_iter().next().unwrap());
+    new_a.write_all(b"hi").unwrap();
+    let mut buf = [0_u8; 2];
+    (&b).read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hi");
+
+    let message = receiver.recv().unwrap().unwrap();
+    assert!(message.data.is_empty());
+    assert!(message.fds.is_empty());
+
+    assert!(receiver.recv().unwrap().is_none());
+}
+
+#[test]
+fn test_message_channel_large() {
+    let (sender, receiver) = message_channel().unwrap();
+    let (a, b) = UnixStream::pair().unwrap();
+
+    // Send from another thread, as a large message won't fit in the socket
+    // buffer.
+    let thread = std::thread::spawn(move || {
+        sender
+            .send(&[7; 1_000_000], &[a.as_filelike(), b.as_filelike()])
+            .unwrap();
+    });
+
+    let message = receiver.recv().unwrap().unwrap();
+    assert_eq!(message.data, vec![7; 1_000_000]);
+    assert_eq!(message.fds.len(), 2);
+    thread.join().unwrap();
+}
+
+#[test]
+fn test_message_channel_datagram() {
+    let (a, b) = UnixDatagram::pair().unwrap();
+    let sender = MessageSender::from(a);
+    let receiver = MessageReceiver::from(b);
+    let file = File::open("Cargo.toml").unwrap();
+
+    sender.send(b"one", &[file.as_filelike()]).unwrap();
+    sender.send(b"two", &[]).unwrap();
+
+    let message = receiver.recv().unwrap().unwrap();
+    assert_eq!(message.data, b"one");
+    assert_eq!(message.fds.len(), 1);
+
+    let message = receiver.recv().unwrap().unwrap();
+    assert_eq!(message.data, b"two");
+    assert!(message.fds.is_empty());
+}
+
+#[test]
+fn test_message_channel_malformed() {
+    let (a, b) = UnixDatagram::pair().unwrap();
+    let receiver = MessageReceiver::from(b);
+
+    a.send(b"bad").unwrap();
+    let err = receiver.recv().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_message_channel_oversized() {
+    let mut header = [0_u8; 8];
+    header[..4].copy_from_slice(&u32::MAX.to_ne_bytes());
+
+    let (mut a, b) = UnixStream::pair().unwrap();
+    let receiver = MessageReceiver::from(b);
+    a.write_all(&header).unwrap();
+    let err = receiver.recv().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "message too long");
+
+    // The oversized datagram is discarded, so the next message gets through.
+    let (a, b) = UnixDatagram::pair().unwrap();
+    let receiver = MessageReceiver::from(b);
+    a.send(&header).unwrap();
+    MessageSender::from(a).send(b"ok", &[]).unwrap();
+    let err = receiver.recv().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(receiver.recv().unwrap().unwrap().data, b"ok");
+
+    let (sender, _receiver) = message_channel().unwrap();
+    let data = vec![0_u8; io_lifetimes::channel::MAX_MESSAGE_LEN + 1];
+    let err = sender.send(&data, &[]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_fd_channel() {
+    let (sender, receiver) = fd_channel::<File>().unwrap();
+
+    sender.send(File::open("Cargo.toml").unwrap()).unwrap();
+
+    let mut contents = String::new();
+    receiver
+        .recv()
+        .unwrap()
+        .unwrap()
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert!(contents.contains("io-lifetimes"));
+
+    drop(sender);
+    assert!(receiver.recv().unwrap().is_none());
+}
+
+#[test]
+fn test_fd_channel_tcp() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let sender = FdSender::<TcpStream>::from(MessageSender::from(a));
+    let receiver = FdReceiver::<TcpStream>::from(MessageReceiver::from(b));
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (server, _) = listener.accept().unwrap();
+
+    sender.send(server).unwrap();
+    let mut server = receiver.recv().unwrap().unwrap();
+
+    client.write_all(b"hello").unwrap();
+    let mut buf = [0_u8; 5];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+}
+
+#[cfg(feature = "os_pipe")]
+#[test]
+fn test_fd_channel_pipe() {
+    let (sender, receiver) = fd_channel::<os_pipe::PipeReader>().unwrap();
+    let (reader, mut writer) = os_pipe::pipe().unwrap();
+
+    sender.send(reader).unwrap();
+    let mut reader = receiver.recv().unwrap().unwrap();
+
+    writer.write_all(b"hello").unwrap();
+    drop(writer);
+    let mut contents = Vec::new();
+    reader.read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, b"hello");
+}
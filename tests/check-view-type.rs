@@ -0,0 +1,71 @@
+#![cfg(all(target_os = "linux", feature = "test-support"))]
+
+use io_lifetimes::test_support::{check_filelike_view_type, check_socketlike_view_type};
+use io_lifetimes::views::{FilelikeViewType, SocketlikeViewType};
+use io_lifetimes::OwnedFd;
+use std::fs::File;
+use std::net::{TcpListener, TcpStream, UdpSocket};
+use std::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
+use std::panic::catch_unwind;
+use std::sync::Mutex;
+
+// File descriptors are process-wide, so the tests mustn't run concurrently.
+static LOCK: Mutex<()> = Mutex::new(());
+
+#[test]
+fn test_std_view_types() {
+    let _lock = LOCK.lock().unwrap();
+
+    check_filelike_view_type::<File>();
+    check_filelike_view_type::<OwnedFd>();
+    #[cfg(feature = "os_pipe")]
+    check_filelike_view_type::<os_pipe::PipeReader>();
+    #[cfg(feature = "os_pipe")]
+    check_filelike_view_type::<os_pipe::PipeWriter>();
+
+    check_socketlike_view_type::<TcpStream>();
+    check_socketlike_view_type::<TcpListener>();
+    check_socketlike_view_type::<UdpSocket>();
+    check_socketlike_view_type::<UnixStream>();
+    check_socketlike_view_type::<UnixListener>();
+    check_socketlike_view_type::<UnixDatagram>();
+}
+
+/// A type which violates the `ViewType` contract by returning a duplicate.
+struct Duplicating(OwnedFd);
+
+impl From<OwnedFd> for Duplicating {
+    fn from(fd: OwnedFd) -> Self {
+        Self(fd)
+    }
+}
+
+impl From<Duplicating> for OwnedFd {
+    fn from(dup: Duplicating) -> Self {
+        dup.0.try_clone().unwrap()
+    }
+}
+
+unsafe impl FilelikeViewType for Duplicating {}
+unsafe impl SocketlikeViewType for Duplicating {}
+
+#[test]
+fn test_violation_detected() {
+    let _lock = LOCK.lock().unwrap();
+
+    let err = catch_unwind(check_filelike_view_type::<Duplicating>).unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(
+        message.contains("returned a different file descriptor"),
+        "{}",
+        message
+    );
+
+    let err = catch_unwind(check_socketlike_view_type::<Duplicating>).unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(
+        message.contains("returned a different file descriptor"),
+        "{}",
+        message
+    );
+}
@@ -0,0 +1,102 @@
+#![cfg(all(unix, feature = "close"))]
+
+use io_lifetimes::views::TryViewError;
+use io_lifetimes::{AsFilelike, AsSocketlike};
+use std::fs::File;
+use std::net::{TcpListener, TcpStream, UdpSocket};
+use std::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
+
+#[test]
+fn test_checked_filelike_views() {
+    let file = File::open("Cargo.toml").unwrap();
+    assert!(file.try_as_filelike_view::<File>().is_ok());
+    assert!(file.try_as_filelike_view::<io_lifetimes::OwnedFd>().is_ok());
+
+    let (stream, _) = UnixStream::pair().unwrap();
+    assert!(stream.try_as_filelike_view::<File>().is_ok());
+}
+
+#[cfg(feature = "os_pipe")]
+#[test]
+fn test_checked_pipe_views() {
+    let (reader, writer) = os_pipe::pipe().unwrap();
+    assert!(reader.try_as_filelike_view::<os_pipe::PipeReader>().is_ok());
+    assert!(writer.try_as_filelike_view::<os_pipe::PipeWriter>().is_ok());
+
+    let file = File::open("Cargo.toml").unwrap();
+    let result = file.try_as_filelike_view::<os_pipe::PipeReader>();
+    match result {
+        Err(TryViewError::Mismatch { reason, .. }) => assert_eq!(reason, "not a pipe"),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_checked_socketlike_views() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let udp = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+    assert!(listener.try_as_socketlike_view::<TcpListener>().is_ok());
+    assert!(stream.try_as_socketlike_view::<TcpStream>().is_ok());
+    assert!(udp.try_as_socketlike_view::<UdpSocket>().is_ok());
+
+    assert!(listener.try_as_socketlike_view::<TcpStream>().is_err());
+    assert!(stream.try_as_socketlike_view::<TcpListener>().is_err());
+    assert!(stream.try_as_socketlike_view::<UdpSocket>().is_err());
+    assert!(udp.try_as_socketlike_view::<TcpStream>().is_err());
+    assert!(stream.try_as_socketlike_view::<UnixStream>().is_err());
+
+    let (a, _b) = UnixStream::pair().unwrap();
+    let (c, _d) = UnixDatagram::pair().unwrap();
+    assert!(a.try_as_socketlike_view::<UnixStream>().is_ok());
+    assert!(c.try_as_socketlike_view::<UnixDatagram>().is_ok());
+    assert!(a.try_as_socketlike_view::<UnixDatagram>().is_err());
+    assert!(a.try_as_socketlike_view::<UnixListener>().is_err());
+    assert!(a.try_as_socketlike_view::<TcpStream>().is_err());
+}
+
+#[test]
+fn test_checked_view_of_non_socket() {
+    let file = File::open("Cargo.toml").unwrap();
+    let err = file.try_as_socketlike_view::<TcpStream>().unwrap_err();
+    assert!(matches!(
+        err,
+        TryViewError::Mismatch {
+            reason: "not a socket",
+            ..
+        }
+    ));
+    assert!(err.to_string().contains("TcpStream"));
+
+    let err: std::io::Error = err.into();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_checked_mut_views() {
+    use std::io::{Read, Write};
+
+    let mut file = File::open("Cargo.toml").unwrap();
+    let mut view = file.try_as_filelike_view_mut::<File>().unwrap();
+    let mut buf = [0_u8; 9];
+    view.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"[package]");
+    drop(view);
+    assert!(file.try_as_socketlike_view_mut::<TcpStream>().is_err());
+
+    let (mut a, mut b) = UnixStream::pair().unwrap();
+    let mut view = a.try_as_socketlike_view_mut::<UnixStream>().unwrap();
+    view.write_all(b"hello").unwrap();
+    drop(view);
+    let mut buf = [0_u8; 5];
+    b.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+    assert!(matches!(
+        a.try_as_socketlike_view_mut::<TcpStream>(),
+        Err(TryViewError::Mismatch {
+            reason: "not an IPv4 or IPv6 socket",
+            ..
+        })
+    ));
+}
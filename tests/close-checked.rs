@@ -0,0 +1,21 @@
+#![cfg(all(feature = "close", any(unix, windows)))]
+
+use io_lifetimes::{CloseChecked, OwnedFilelike};
+use std::fs::File;
+use std::io::Write;
+
+#[test]
+fn test_close_checked() {
+    let dir = std::env::temp_dir();
+    let path = dir.join(format!("io-lifetimes-close-checked-{}", std::process::id()));
+
+    let mut file = File::create(&path).unwrap();
+    file.write_all(b"hello").unwrap();
+    file.close().unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), b"hello");
+
+    let owned = OwnedFilelike::from(File::open(&path).unwrap());
+    owned.close().unwrap();
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,120 @@
+#![cfg(all(target_os = "linux", feature = "close"))]
+
+use io_lifetimes::command::CommandFdExt;
+use io_lifetimes::OwnedFd;
+use std::fs::{read_link, File};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+/// Spawn a shell which waits for a line on its stdin before exiting, and
+/// wait for it to execute, so that its `/proc/<pid>/fd` shows the file
+/// descriptors it inherited.
+fn spawn_shell(map: impl FnOnce(Command) -> std::io::Result<Child>) -> Child {
+    let mut command = Command::new("/bin/sh");
+    command
+        .args(["-c", "echo ready; read line"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped());
+    let mut child = map(command).unwrap();
+
+    let mut line = String::new();
+    BufReader::new(child.stdout.as_mut().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    assert_eq!(line, "ready\n");
+    child
+}
+
+fn finish(mut child: Child) {
+    writeln!(child.stdin.take().unwrap()).unwrap();
+    assert!(child.wait().unwrap().success());
+}
+
+fn child_fd(child: &Child, raw: i32) -> Option<PathBuf> {
+    read_link(format!("/proc/{}/fd/{}", child.id(), raw)).ok()
+}
+
+fn manifest(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(name)
+}
+
+/// Open `name` with a file descriptor number of at least `min`.
+fn open_above(name: &str, min: i32) -> OwnedFd {
+    let file = File::open(manifest(name)).unwrap();
+    let raw = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_DUPFD_CLOEXEC, min) };
+    assert_ne!(raw, -1);
+    unsafe { OwnedFd::from_raw_fd(raw) }
+}
+
+#[test]
+fn test_map_fd() {
+    let file = open_above("Cargo.toml", 200);
+    let source = file.as_raw_fd();
+
+    let child = spawn_shell(|command| command.map_fd(&file, 3).spawn());
+    assert_eq!(child_fd(&child, 3), Some(manifest("Cargo.toml")));
+
+    // The source is close-on-exec, so only the target is inherited.
+    assert_eq!(child_fd(&child, source), None);
+    finish(child);
+}
+
+#[test]
+fn test_map_fd_swap() {
+    let a = open_above("Cargo.toml", 300);
+    let b = open_above("README.md", 300);
+    let (a_raw, b_raw) = (a.as_raw_fd(), b.as_raw_fd());
+
+    // Map each file descriptor onto the other's number.
+    let child = spawn_shell(|command| command.map_fd(&a, b_raw).map_fd(&b, a_raw).spawn());
+    assert_eq!(child_fd(&child, a_raw), Some(manifest("README.md")));
+    assert_eq!(child_fd(&child, b_raw), Some(manifest("Cargo.toml")));
+    finish(child);
+}
+
+#[test]
+fn test_map_fd_stdio_and_last_wins() {
+    let a = File::open(manifest("Cargo.toml")).unwrap();
+    let b = File::open(manifest("README.md")).unwrap();
+
+    // Mapping onto a standard I/O number overrides the `Command`'s setting,
+    // and the last mapping for a target takes effect.
+    let mut command = Command::new("/bin/sh");
+    command.args(["-c", "grep -q '^<div' && grep -q '^\\[package\\]' <&4"]);
+    let status = command
+        .map_fd(&a, 0)
+        .map_fd(&a, 4)
+        .map_fd(&b, 0)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[test]
+fn test_command_mut() {
+    let file = File::open(manifest("Cargo.toml")).unwrap();
+
+    // The `Command` can still be configured after mappings are added.
+    let mut mapped = Command::new("/bin/sh").map_fd(&file, 3);
+    mapped
+        .command_mut()
+        .args([
+            "-c",
+            "test \"$MAPPED\" = yes && grep -q '^\\[package\\]' <&3",
+        ])
+        .env("MAPPED", "yes");
+    assert_eq!(mapped.command().get_program(), "/bin/sh");
+    assert!(mapped.status().unwrap().success());
+}
+
+#[test]
+fn test_map_fd_invalid() {
+    let file = File::open(manifest("Cargo.toml")).unwrap();
+    let err = Command::new("/bin/true")
+        .map_fd(&file, -1)
+        .spawn()
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "derive")]
+#![cfg(not(target_os = "wasi"))]
+
+use io_lifetimes::{
+    AsFilelike, AsSocketlike, FromFilelike, FromSocketlike, IntoFilelike, IntoSocketlike,
+    OwnedFilelike, OwnedSocketlike,
+};
+use std::fs::File;
+use std::io::Read;
+use std::net::{TcpListener, TcpStream};
+
+#[derive(AsFilelike, IntoFilelike, FromFilelike)]
+struct Tuple(File);
+
+#[derive(AsFilelike, IntoFilelike, FromFilelike)]
+#[fd(unsafe_view_type)]
+struct Named {
+    label: String,
+    #[fd]
+    filelike: OwnedFilelike,
+    count: usize,
+}
+
+#[derive(AsSocketlike, IntoSocketlike, FromSocketlike)]
+#[fd(unsafe_view_type)]
+struct Socket<T: Default> {
+    #[fd]
+    socketlike: OwnedSocketlike,
+    extra: T,
+}
+
+#[test]
+fn test_derive_tuple() {
+    let tuple = Tuple::from_into_filelike(File::open("Cargo.toml").unwrap());
+    let mut s = String::new();
+    (&*tuple.as_filelike_view::<File>())
+        .read_to_string(&mut s)
+        .unwrap();
+    assert!(s.contains("io-lifetimes"));
+
+    let file = File::from_into_filelike(tuple);
+    assert!(file.metadata().unwrap().is_file());
+}
+
+#[test]
+fn test_derive_named() {
+    let named = Named::from_into_filelike(File::open("Cargo.toml").unwrap());
+    assert!(named.label.is_empty());
+    assert_eq!(named.count, 0);
+
+    // `#[fd(unsafe_view_type)]` lets `Named` be the target of a view.
+    let file = File::open("Cargo.toml").unwrap();
+    let view = file.as_filelike_view::<Named>();
+    assert_eq!(view.count, 0);
+    drop(view);
+
+    let _: OwnedFilelike = named.into_filelike();
+}
+
+#[test]
+fn test_derive_socketlike() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let socket = Socket::<Vec<u8>>::from_into_socketlike(listener);
+    assert!(socket.extra.is_empty());
+    assert_eq!(
+        socket
+            .as_socketlike_view::<TcpListener>()
+            .local_addr()
+            .unwrap(),
+        addr
+    );
+
+    let _client = TcpStream::connect(addr).unwrap();
+    let listener = TcpListener::from_into_socketlike(socket);
+    listener.accept().unwrap();
+
+    let view = listener.as_socketlike_view::<Socket<u8>>();
+    assert_eq!(view.extra, 0);
+}
@@ -0,0 +1,60 @@
+#![cfg(all(unix, feature = "close"))]
+
+use io_lifetimes::describe::DescribeFd;
+use std::fs::File;
+use std::net::{TcpListener, TcpStream};
+use std::os::unix::net::UnixDatagram;
+
+#[test]
+fn test_describe_file() {
+    let file = File::open("Cargo.toml").unwrap();
+
+    let display = DescribeFd(&file).to_string();
+    assert!(display.contains("regular file"), "{}", display);
+    assert!(display.contains("read-only"), "{}", display);
+    assert!(display.contains("cloexec"), "{}", display);
+    assert!(!display.contains("nonblocking"), "{}", display);
+
+    let debug = format!("{:?}", DescribeFd(&file));
+    assert!(debug.starts_with("DescribeFd { fd: "), "{}", debug);
+    assert!(debug.contains("ReadOnly"), "{}", debug);
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    assert!(debug.contains("Cargo.toml"), "{}", debug);
+}
+
+#[test]
+fn test_describe_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let client = TcpStream::connect(addr).unwrap();
+    client.set_nonblocking(true).unwrap();
+
+    let display = DescribeFd(&client).to_string();
+    assert!(display.contains("stream socket"), "{}", display);
+    assert!(display.contains(&format!("-> {}", addr)), "{}", display);
+    assert!(display.contains("nonblocking"), "{}", display);
+
+    let display = DescribeFd(&listener).to_string();
+    assert!(display.contains(&addr.to_string()), "{}", display);
+    assert!(!display.contains("->"), "{}", display);
+}
+
+#[test]
+fn test_describe_unix() {
+    let (a, _b) = UnixDatagram::pair().unwrap();
+
+    let display = DescribeFd(&a).to_string();
+    assert!(display.contains("datagram socket"), "{}", display);
+    assert!(display.contains("read-write"), "{}", display);
+}
+
+#[cfg(feature = "describe")]
+#[test]
+fn test_describe_views() {
+    use io_lifetimes::AsFilelike;
+
+    let file = File::open("Cargo.toml").unwrap();
+    let debug = format!("{:?}", file.as_filelike_view::<File>());
+    assert!(debug.contains("DescribeFd"), "{}", debug);
+    assert!(debug.contains("regular file"), "{}", debug);
+}
@@ -0,0 +1,69 @@
+#![cfg(all(target_os = "linux", feature = "close"))]
+
+use io_lifetimes::fd_set::OwnedFdSet;
+use io_lifetimes::raw::AsRawFilelike;
+use io_lifetimes::OwnedFd;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Mutex;
+
+// The tests check which file descriptors are open, so they mustn't run
+// concurrently with each other.
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn is_open(raw: i32) -> bool {
+    Path::new(&format!("/proc/self/fd/{}", raw)).exists()
+}
+
+fn open_file() -> OwnedFd {
+    File::open("Cargo.toml").unwrap().into()
+}
+
+#[test]
+fn test_fd_set_drop() {
+    let _guard = LOCK.lock().unwrap();
+
+    // Open a contiguous run of file descriptors, with a gap in the middle
+    // so that there are two runs.
+    let mut set: OwnedFdSet = (0..64).map(|_| open_file()).collect();
+    let raws: Vec<_> = set.iter().map(|fd| fd.as_raw_filelike()).collect();
+    assert_eq!(raws.len(), 64);
+    assert!(raws.windows(2).all(|w| w[0] < w[1]));
+    let gap = set.remove(raws[32]).unwrap();
+    assert!(!set.contains(raws[32]));
+    assert_eq!(set.len(), 63);
+
+    assert!(raws.iter().all(|raw| is_open(*raw)));
+    drop(set);
+    for raw in &raws {
+        assert_eq!(is_open(*raw), *raw == raws[32], "{}", raw);
+    }
+    drop(gap);
+    assert!(!is_open(raws[32]));
+}
+
+#[test]
+fn test_fd_set_take() {
+    let _guard = LOCK.lock().unwrap();
+
+    let mut set = OwnedFdSet::new();
+    assert!(set.is_empty());
+    set.extend((0..4).map(|_| open_file()));
+    let raws: Vec<_> = set.iter().map(|fd| fd.as_raw_filelike()).collect();
+
+    let first = set.pop_first().unwrap();
+    assert_eq!(first.as_raw_filelike(), raws[0]);
+    let last = set.pop_last().unwrap();
+    assert_eq!(last.as_raw_filelike(), raws[3]);
+    assert_eq!(set.get(raws[1]).unwrap().as_raw_filelike(), raws[1]);
+
+    // Moving the file descriptors out of the set doesn't close them.
+    let rest: Vec<OwnedFd> = set.into_iter().collect();
+    assert_eq!(rest.len(), 2);
+    assert!(raws.iter().all(|raw| is_open(*raw)));
+
+    drop(rest);
+    drop(first);
+    drop(last);
+    assert!(!raws.iter().any(|raw| is_open(*raw)));
+}
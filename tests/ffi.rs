@@ -76,3 +76,196 @@ fn test_file_found() {
     .try_into();
     assert!(handle.is_ok());
 }
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use io_lifetimes::example_ffi::*;
+    use io_lifetimes::{AsFd, BorrowedFd, OwnedFd};
+    use std::mem::{size_of, MaybeUninit};
+    use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd};
+
+    fn cwd() -> BorrowedFd<'static> {
+        unsafe { BorrowedFd::borrow_raw(AT_FDCWD) }
+    }
+
+    fn is_cloexec(fd: BorrowedFd<'_>) -> bool {
+        let flags = unsafe { fcntl(fd, F_GETFD) };
+        assert_ne!(flags, -1);
+        flags & FD_CLOEXEC != 0
+    }
+
+    fn write_all(fd: BorrowedFd<'_>, bytes: &[u8]) {
+        let n = unsafe { write(fd, bytes.as_ptr().cast(), bytes.len()) };
+        assert_eq!(n, bytes.len() as isize);
+    }
+
+    fn read_some(fd: BorrowedFd<'_>) -> Vec<u8> {
+        let mut buf = [0_u8; 64];
+        let n = unsafe { read(fd, buf.as_mut_ptr().cast(), buf.len()) };
+        assert!(n >= 0);
+        buf[..n as usize].to_vec()
+    }
+
+    #[test]
+    fn test_openat() {
+        let dir = unsafe {
+            openat(
+                cwd(),
+                "src\0".as_ptr().cast(),
+                O_RDONLY | O_DIRECTORY | O_CLOEXEC,
+            )
+        }
+        .unwrap();
+        let file = unsafe {
+            openat(
+                dir.as_fd(),
+                "lib.rs\0".as_ptr().cast(),
+                O_RDONLY | O_CLOEXEC,
+            )
+        }
+        .unwrap();
+        assert!(!read_some(file.as_fd()).is_empty());
+        assert!(unsafe {
+            openat(
+                dir.as_fd(),
+                "no-such-file\0".as_ptr().cast(),
+                O_RDONLY | O_CLOEXEC,
+            )
+        }
+        .is_none());
+    }
+
+    #[test]
+    fn test_dup() {
+        let file = unsafe { open("Cargo.toml\0".as_ptr().cast(), O_RDONLY | O_CLOEXEC) }.unwrap();
+
+        let dup = unsafe { dup(file.as_fd()) }.unwrap();
+        assert_ne!(dup.as_raw_fd(), file.as_raw_fd());
+        assert!(!is_cloexec(dup.as_fd()));
+
+        let dupfd = fcntl_dupfd_cloexec(file.as_fd(), 0).unwrap();
+        assert!(is_cloexec(dupfd.as_fd()));
+
+        // The variadic `fcntl` returns the raw new file descriptor.
+        let raw = unsafe { fcntl(file.as_fd(), F_DUPFD_CLOEXEC, 0) };
+        assert_ne!(raw, -1);
+        drop(unsafe { OwnedFd::from_raw_fd(raw) });
+
+        // Replace `dup` with a duplicate of `file`, reusing its number.
+        let target = dup.into_raw_fd();
+        let replaced = unsafe { dup3(file.as_fd(), target, O_CLOEXEC) }.unwrap();
+        assert_eq!(replaced.as_raw_fd(), target);
+        assert!(is_cloexec(replaced.as_fd()));
+    }
+
+    #[test]
+    fn test_pipes() {
+        let mut fds = MaybeUninit::<[OwnedFd; 2]>::uninit();
+        assert_eq!(unsafe { pipe(fds.as_mut_ptr()) }, 0);
+        let [reader, writer] = unsafe { fds.assume_init() };
+        write_all(writer.as_fd(), b"pipe");
+        assert_eq!(read_some(reader.as_fd()), b"pipe");
+
+        let mut fds = MaybeUninit::<[OwnedFd; 2]>::uninit();
+        assert_eq!(
+            unsafe { pipe2(fds.as_mut_ptr(), O_CLOEXEC | O_NONBLOCK) },
+            0
+        );
+        let [reader, writer] = unsafe { fds.assume_init() };
+        assert!(is_cloexec(reader.as_fd()));
+        assert_ne!(unsafe { fcntl(writer.as_fd(), F_GETFL) } & O_NONBLOCK, 0);
+        write_all(writer.as_fd(), b"pipe2");
+
+        let mut available = 0;
+        assert_eq!(
+            unsafe { ioctl(reader.as_fd(), FIONREAD, &mut available as *mut i32) },
+            0
+        );
+        assert_eq!(available, 5);
+    }
+
+    #[test]
+    fn test_sockets() {
+        let mut fds = MaybeUninit::<[OwnedFd; 2]>::uninit();
+        assert_eq!(
+            unsafe { socketpair(AF_UNIX, SOCK_STREAM | SOCK_CLOEXEC, 0, fds.as_mut_ptr()) },
+            0
+        );
+        let [a, b] = unsafe { fds.assume_init() };
+        write_all(a.as_fd(), b"socketpair");
+        assert_eq!(read_some(b.as_fd()), b"socketpair");
+
+        let udp = unsafe { socket(AF_INET, SOCK_DGRAM | SOCK_CLOEXEC, 0) }.unwrap();
+        assert!(is_cloexec(udp.as_fd()));
+        assert!(unsafe { socket(-1, SOCK_STREAM, 0) }.is_none());
+    }
+
+    #[test]
+    fn test_accept4() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let _client = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+
+        let mut addr = MaybeUninit::<libc::sockaddr_storage>::uninit();
+        let mut len = size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+        let accepted = unsafe {
+            accept4(
+                listener.as_fd(),
+                addr.as_mut_ptr().cast(),
+                &mut len,
+                SOCK_CLOEXEC | SOCK_NONBLOCK,
+            )
+        }
+        .unwrap();
+        assert!(is_cloexec(accepted.as_fd()));
+        assert_ne!(unsafe { fcntl(accepted.as_fd(), F_GETFL) } & O_NONBLOCK, 0);
+    }
+
+    #[test]
+    fn test_fstat() {
+        let file = unsafe { open("Cargo.toml\0".as_ptr().cast(), O_RDONLY | O_CLOEXEC) }.unwrap();
+        let mut stat = MaybeUninit::<libc::stat>::uninit();
+        assert_eq!(unsafe { fstat(file.as_fd(), stat.as_mut_ptr()) }, 0);
+        let stat = unsafe { stat.assume_init() };
+        assert_eq!(stat.st_mode & libc::S_IFMT, libc::S_IFREG);
+        assert_eq!(
+            stat.st_size as u64,
+            std::fs::metadata("Cargo.toml").unwrap().len()
+        );
+    }
+
+    #[test]
+    fn test_ioctl_cloexec() {
+        let file = unsafe { open("Cargo.toml\0".as_ptr().cast(), O_RDONLY) }.unwrap();
+        assert!(!is_cloexec(file.as_fd()));
+        assert_eq!(unsafe { ioctl(file.as_fd(), FIOCLEX) }, 0);
+        assert!(is_cloexec(file.as_fd()));
+        assert_eq!(unsafe { ioctl(file.as_fd(), FIONCLEX) }, 0);
+        assert!(!is_cloexec(file.as_fd()));
+    }
+
+    #[test]
+    fn test_memfd_and_eventfd() {
+        let memfd = unsafe { memfd_create("io-lifetimes\0".as_ptr().cast(), MFD_CLOEXEC) }.unwrap();
+        assert!(is_cloexec(memfd.as_fd()));
+        write_all(memfd.as_fd(), b"memfd");
+
+        let event = unsafe { eventfd(3, EFD_CLOEXEC | EFD_NONBLOCK) }.unwrap();
+        assert_eq!(read_some(event.as_fd()), 3_u64.to_ne_bytes());
+    }
+
+    #[test]
+    fn test_close_range() {
+        // Make a contiguous range of file descriptors to close, by
+        // duplicating a file onto numbers above the ones in use.
+        let file = unsafe { open("Cargo.toml\0".as_ptr().cast(), O_RDONLY | O_CLOEXEC) }.unwrap();
+        let first = fcntl_dupfd_cloexec(file.as_fd(), 1000).unwrap();
+        let first = first.into_raw_fd();
+        let second = unsafe { dup3(file.as_fd(), first + 1, O_CLOEXEC) }.unwrap();
+        let second = second.into_raw_fd();
+
+        assert_eq!(unsafe { close_range(first as _, second as _, 0) }, 0);
+        for raw in [first, second] {
+            assert_eq!(unsafe { libc::fcntl(raw, libc::F_GETFD) }, -1);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+#![cfg(any(unix, windows))]
+
+use io_lifetimes::grip::{AsGrip, GripKind, GripView, IntoGrip};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+fn read_all<G: AsGrip>(grip: &G) -> String {
+    let mut s = String::new();
+    match grip.as_grip_view::<File, TcpStream>() {
+        GripView::Filelike(file) => (&*file).read_to_string(&mut s).unwrap(),
+        GripView::Socketlike(socket) => (&*socket).read_to_string(&mut s).unwrap(),
+    };
+    s
+}
+
+#[test]
+fn test_grip_kind() {
+    let file = File::open("Cargo.toml").unwrap();
+    assert_eq!(file.grip_kind(), GripKind::Filelike);
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    assert_eq!(listener.grip_kind(), GripKind::Socketlike);
+    let borrowed = &listener;
+    assert_eq!(borrowed.grip_kind(), GripKind::Socketlike);
+}
+
+#[test]
+fn test_grip_view() {
+    let file = File::open("Cargo.toml").unwrap();
+    assert!(read_all(&file).contains("io-lifetimes"));
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (server, _) = listener.accept().unwrap();
+    client.write_all(b"hello").unwrap();
+    drop(client);
+    assert_eq!(read_all(&server), "hello");
+}
+
+#[test]
+fn test_into_grip() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let owned = listener.into_grip();
+    assert_eq!(owned.grip_kind(), GripKind::Socketlike);
+    assert_eq!(
+        owned.as_grip_view::<File, TcpListener>().kind(),
+        GripKind::Socketlike
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_from_grip() {
+    use io_lifetimes::grip::FromGrip;
+
+    let owned = File::open("Cargo.toml").unwrap().into_grip();
+    let file = File::from_grip(owned);
+    assert!(read_all(&file).contains("io-lifetimes"));
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let listener = TcpListener::from_into_grip(listener);
+    assert_eq!(listener.local_addr().unwrap(), addr);
+}
@@ -0,0 +1,65 @@
+#![cfg(all(unix, feature = "close"))]
+
+use io_lifetimes::kind::{Classify, Kind};
+use io_lifetimes::views::SocketType;
+use io_lifetimes::AsSocketlike;
+use std::fs::File;
+use std::io;
+use std::net::{TcpListener, UdpSocket};
+use std::os::unix::net::UnixStream;
+
+#[test]
+fn test_kind_files() {
+    let file = File::open("Cargo.toml").unwrap();
+    assert_eq!(file.kind().unwrap(), Kind::RegularFile);
+
+    let dir = File::open(".").unwrap();
+    assert_eq!(dir.kind().unwrap(), Kind::Directory);
+
+    let null = File::open("/dev/null").unwrap();
+    assert_eq!(null.kind().unwrap(), Kind::CharacterDevice);
+    assert!(null.kind().unwrap().is_character_device());
+}
+
+#[test]
+fn test_kind_pipe() {
+    let mut fds = [0; 2];
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    let (reader, writer) = unsafe {
+        use std::os::unix::io::FromRawFd;
+        (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1]))
+    };
+    assert_eq!(reader.kind().unwrap(), Kind::Pipe);
+    assert_eq!(writer.kind().unwrap(), Kind::Pipe);
+}
+
+#[test]
+fn test_kind_sockets() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    assert_eq!(
+        listener.kind().unwrap(),
+        Kind::Socket(Some(SocketType::Stream))
+    );
+
+    let udp = UdpSocket::bind("127.0.0.1:0").unwrap();
+    assert_eq!(
+        udp.kind().unwrap(),
+        Kind::Socket(Some(SocketType::Datagram))
+    );
+
+    let (a, _b) = UnixStream::pair().unwrap();
+    assert!(a.kind().unwrap().is_socket());
+}
+
+fn generic_socketlike<S: AsSocketlike>(socket: &S) -> io::Result<Kind> {
+    socket.kind()
+}
+
+#[test]
+fn test_kind_generic() {
+    let (a, _b) = UnixStream::pair().unwrap();
+    assert_eq!(
+        generic_socketlike(&a).unwrap(),
+        Kind::Socket(Some(SocketType::Stream))
+    );
+}
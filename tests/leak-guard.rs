@@ -0,0 +1,56 @@
+#![cfg(all(target_os = "linux", feature = "test-support"))]
+
+use io_lifetimes::raw::{FromRawFilelike, IntoRawFilelike};
+use io_lifetimes::test_support::LeakGuard;
+use io_lifetimes::{IntoFilelike, OwnedFilelike};
+use std::fs::File;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Mutex;
+
+// File descriptors are process-wide, so the tests mustn't run concurrently.
+static LOCK: Mutex<()> = Mutex::new(());
+
+#[test]
+fn test_no_leaks() {
+    let _lock = LOCK.lock().unwrap();
+    let guard = LeakGuard::new();
+
+    let file = File::open("Cargo.toml").unwrap();
+    let owned = file.into_filelike();
+    drop(File::from(owned));
+
+    guard.assert_no_leaks();
+}
+
+#[test]
+fn test_leak_detected() {
+    let _lock = LOCK.lock().unwrap();
+    let guard = LeakGuard::new();
+
+    let raw = File::open("Cargo.toml").unwrap().into_raw_filelike();
+
+    let leaks = guard.leaks();
+    assert_eq!(leaks.len(), 1);
+    assert_eq!(leaks[0].raw_fd(), raw);
+    assert!(leaks[0].target().unwrap().ends_with("Cargo.toml"));
+
+    let err = catch_unwind(AssertUnwindSafe(|| guard.assert_no_leaks())).unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(message.contains("Cargo.toml"), "{}", message);
+
+    // Clean up, so that the guard doesn't panic when it's dropped.
+    drop(unsafe { OwnedFilelike::from_raw_filelike(raw) });
+}
+
+#[test]
+fn test_allow_runtime() {
+    let _lock = LOCK.lock().unwrap();
+    let guard = LeakGuard::new().allow_runtime();
+
+    let epoll = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+    assert_ne!(epoll, -1);
+    guard.assert_no_leaks();
+
+    drop(guard);
+    drop(unsafe { OwnedFilelike::from_raw_filelike(epoll) });
+}
@@ -16,6 +16,7 @@ use std::os::windows::io::{FromRawSocket, IntoRawSocket, RawSocket};
 
 #[cfg(any(unix, target_os = "wasi"))]
 #[test]
+#[allow(clippy::unnecessary_literal_unwrap)]
 fn test_niche_optimizations() {
     assert_eq!(size_of::<Option<OwnedFd>>(), size_of::<RawFd>());
     assert_eq!(size_of::<Option<BorrowedFd<'static>>>(), size_of::<RawFd>());
@@ -39,6 +40,7 @@ fn test_niche_optimizations() {
 
 #[cfg(windows)]
 #[test]
+#[allow(clippy::unnecessary_literal_unwrap)]
 fn test_niche_optimizations_socket() {
     assert_eq!(size_of::<Option<OwnedSocket>>(), size_of::<RawSocket>());
     assert_eq!(
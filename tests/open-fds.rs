@@ -0,0 +1,79 @@
+#![cfg(all(unix, feature = "close"))]
+
+use io_lifetimes::open_fds::{open_fds, AccessMode};
+use io_lifetimes::raw::AsRawFilelike;
+use io_lifetimes::AsFilelike;
+use std::fs::File;
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::UnixStream;
+
+#[test]
+fn test_open_fds() {
+    let file = File::open("Cargo.toml").unwrap();
+    let (a, _b) = UnixStream::pair().unwrap();
+    let fds = open_fds().unwrap();
+
+    assert!(fds.windows(2).all(|w| w[0].raw_fd() < w[1].raw_fd()));
+
+    let raw = file.as_filelike().as_raw_filelike();
+    let entry = fds.iter().find(|fd| fd.raw_fd() == raw).unwrap();
+    assert!(entry.file_type().is_file());
+    assert!(entry.is_cloexec());
+    assert_eq!(entry.access_mode(), Some(AccessMode::ReadOnly));
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    assert_eq!(
+        entry.target().unwrap(),
+        std::fs::canonicalize("Cargo.toml").unwrap()
+    );
+
+    // The borrowed file descriptor refers to the same file.
+    let borrowed = unsafe { entry.borrow_raw() };
+    assert_eq!(
+        borrowed
+            .as_filelike_view::<File>()
+            .metadata()
+            .unwrap()
+            .len(),
+        file.metadata().unwrap().len()
+    );
+
+    let raw = a.as_filelike().as_raw_filelike();
+    let entry = fds.iter().find(|fd| fd.raw_fd() == raw).unwrap();
+    assert!(entry.file_type().is_socket());
+    assert_eq!(entry.access_mode(), Some(AccessMode::ReadWrite));
+}
+
+#[test]
+fn test_open_fds_write_only() {
+    let path = std::env::temp_dir().join(format!("io-lifetimes-open-fds-{}", std::process::id()));
+    let file = File::create(&path).unwrap();
+    let raw = file.as_filelike().as_raw_filelike();
+
+    let fds = open_fds().unwrap();
+    let entry = fds.iter().find(|fd| fd.raw_fd() == raw).unwrap();
+    assert_eq!(entry.access_mode(), Some(AccessMode::WriteOnly));
+    assert!(entry.access_mode().unwrap().is_writable());
+    assert!(!entry.access_mode().unwrap().is_readable());
+
+    drop(file);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_open_fds_o_path() {
+    use std::fs::OpenOptions;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    // `O_RDONLY` is zero, but `O_PATH` file descriptors aren't readable.
+    let file = OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_PATH)
+        .open("Cargo.toml")
+        .unwrap();
+    let raw = file.as_filelike().as_raw_filelike();
+
+    let fds = open_fds().unwrap();
+    let entry = fds.iter().find(|fd| fd.raw_fd() == raw).unwrap();
+    assert_eq!(entry.access_mode(), None);
+}
@@ -0,0 +1,101 @@
+#![cfg(all(unix, feature = "close"))]
+
+use io_lifetimes::scm_rights::{recv_with_fds, send_with_fds};
+use io_lifetimes::{AsFd, AsFilelike, BorrowedFd};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::{UnixDatagram, UnixStream};
+
+fn is_cloexec(fd: BorrowedFd<'_>) -> bool {
+    let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFD) };
+    assert_ne!(flags, -1);
+    flags & libc::FD_CLOEXEC != 0
+}
+
+#[test]
+fn test_send_recv_stream() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let (reader, writer) = UnixStream::pair().unwrap();
+
+    assert_eq!(
+        send_with_fds(&a, b"hello", &[reader.as_filelike(), writer.as_filelike()]).unwrap(),
+        5
+    );
+
+    let mut buf = [0_u8; 16];
+    let received = recv_with_fds(&b, &mut buf, 4).unwrap();
+    assert_eq!(&buf[..received.len], b"hello");
+    assert!(!received.fds_truncated);
+    assert_eq!(received.fds.len(), 2);
+    assert!(received.fds.iter().all(|fd| is_cloexec(fd.as_fd())));
+
+    // The received descriptors refer to the same sockets as the originals.
+    let mut fds = received.fds.into_iter();
+    let mut new_reader = UnixStream::from(fds.next().unwrap());
+    let mut new_writer = UnixStream::from(fds.next().unwrap());
+    new_writer.write_all(b"world").unwrap();
+    let mut buf = [0_u8; 5];
+    (&reader).read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"world");
+    (&writer).write_all(b"again").unwrap();
+    new_reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"again");
+}
+
+#[test]
+fn test_send_recv_datagram() {
+    let (a, b) = UnixDatagram::pair().unwrap();
+    let file = File::open("Cargo.toml").unwrap();
+
+    send_with_fds(&a, b"file", &[file.as_filelike()]).unwrap();
+    send_with_fds(&a, b"no fds", &[]).unwrap();
+
+    let mut buf = [0_u8; 16];
+    let received = recv_with_fds(&b, &mut buf, 1).unwrap();
+    assert_eq!(&buf[..received.len], b"file");
+    let mut contents = String::new();
+    File::from(received.fds.into_iter().next().unwrap())
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert!(contents.contains("io-lifetimes"));
+
+    let mut buf = [0_u8; 2];
+    let received = recv_with_fds(&b, &mut buf, 1).unwrap();
+    assert_eq!(&buf[..received.len], b"no");
+    assert!(received.fds.is_empty());
+    assert!(received.data_truncated);
+}
+
+#[test]
+fn test_recv_truncated() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let file = File::open("Cargo.toml").unwrap();
+    let fds = [file.as_filelike(); 8];
+
+    send_with_fds(&a, b"x", &fds).unwrap();
+
+    // Ask for room for one descriptor. The OS may deliver a few more if
+    // there's padding in the buffer, but not all of them, and the ones it
+    // does deliver are still returned as `OwnedFd`s.
+    let mut buf = [0_u8; 1];
+    let received = recv_with_fds(&b, &mut buf, 1).unwrap();
+    assert_eq!(received.len, 1);
+    assert!(received.fds_truncated);
+    assert!(!received.fds.is_empty());
+    assert!(received.fds.len() < fds.len());
+}
+
+#[test]
+fn test_recv_no_room_for_fds() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let file = File::open("Cargo.toml").unwrap();
+
+    send_with_fds(&a, b"x", &[file.as_filelike()]).unwrap();
+
+    let mut buf = [0_u8; 1];
+    let received = recv_with_fds(&b, &mut buf, 0).unwrap();
+    assert_eq!(received.len, 1);
+    assert!(received.fds_truncated);
+    assert!(received.fds.is_empty());
+}
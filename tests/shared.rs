@@ -0,0 +1,64 @@
+use io_lifetimes::shared::{SharedFilelike, SharedSocketlike, WeakFilelike};
+use io_lifetimes::{AsFilelike, AsSocketlike, OwnedFilelike};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+#[test]
+fn test_shared_filelike() {
+    let shared = SharedFilelike::new(File::open("Cargo.toml").unwrap().into());
+    let other = shared.clone();
+    assert!(shared.ptr_eq(&other));
+    assert_eq!(shared.strong_count(), 2);
+
+    // Both refer to the same file descriptor, rather than a duplicate.
+    assert_eq!(
+        format!("{:?}", shared.as_filelike()),
+        format!("{:?}", other.as_filelike())
+    );
+
+    let mut contents = String::new();
+    (&*other.as_filelike_view::<File>())
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert!(contents.contains("io-lifetimes"));
+
+    // `try_unwrap` fails while there's another reference.
+    let shared = shared.try_unwrap().unwrap_err();
+    drop(other);
+    let owned: OwnedFilelike = shared.try_unwrap().unwrap();
+    drop(owned);
+}
+
+#[test]
+fn test_weak_filelike() {
+    let shared = SharedFilelike::new(File::open("Cargo.toml").unwrap().into());
+    let weak = shared.downgrade();
+    assert!(weak.upgrade().unwrap().ptr_eq(&shared));
+    assert_eq!(shared.strong_count(), 1);
+
+    drop(shared);
+    assert!(weak.upgrade().is_none());
+    assert!(WeakFilelike::new().upgrade().is_none());
+}
+
+#[test]
+fn test_shared_socketlike() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (server, _) = listener.accept().unwrap();
+
+    let shared = SharedSocketlike::new(server.into());
+    let other = shared.clone();
+    let thread = std::thread::spawn(move || {
+        (&*other.as_socketlike_view::<TcpStream>())
+            .write_all(b"hello")
+            .unwrap();
+    });
+    thread.join().unwrap();
+
+    let mut buf = [0_u8; 5];
+    client.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+    drop(shared);
+}
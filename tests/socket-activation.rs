@@ -0,0 +1,162 @@
+#![cfg(all(target_os = "linux", feature = "close"))]
+
+//! Tests for socket activation. Each test spawns this test binary again, as
+//! a service manager would, with file descriptors and environment variables
+//! prepared, to run `activated_child` with the scenario named in
+//! `IO_LIFETIMES_ACTIVATION_SCENARIO`.
+
+use io_lifetimes::command::CommandFdExt;
+use io_lifetimes::socket_activation::{listen_fds, LISTEN_FDS_START};
+use io_lifetimes::AsFd;
+use std::env;
+use std::fs::File;
+use std::net::{SocketAddr, TcpListener};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+const SCENARIO: &str = "IO_LIFETIMES_ACTIVATION_SCENARIO";
+
+/// Run `activated_child` in a child process with `scenario`, setting
+/// `LISTEN_PID` to the child's process ID. The child's test harness output
+/// is discarded, but its panic messages are printed to stderr.
+fn spawn_child(scenario: &str, configure: impl FnOnce(Command) -> bool) {
+    let mut command = Command::new("/bin/sh");
+    command
+        .args(["-c", "LISTEN_PID=$$ exec \"$0\" \"$@\""])
+        .arg(env::current_exe().unwrap())
+        .args(["activated_child", "--exact", "--nocapture"])
+        .env(SCENARIO, scenario)
+        .stdout(Stdio::null());
+    assert!(configure(command), "scenario {} failed", scenario);
+}
+
+fn is_cloexec(fd: impl AsFd) -> bool {
+    let flags = unsafe { libc::fcntl(fd.as_fd().as_raw_fd(), libc::F_GETFD) };
+    assert_ne!(flags, -1);
+    flags & libc::FD_CLOEXEC != 0
+}
+
+fn assert_env_removed() {
+    for var in ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"] {
+        assert!(env::var_os(var).is_none(), "{}", var);
+    }
+}
+
+#[test]
+fn activated_child() {
+    let scenario = match env::var(SCENARIO) {
+        Ok(scenario) => scenario,
+        // Not running as a child.
+        Err(_) => return,
+    };
+
+    match scenario.as_str() {
+        "listeners" => {
+            let fds = listen_fds().unwrap();
+            assert_env_removed();
+            assert_eq!(fds.len(), 2);
+            assert_eq!(fds[0].name(), "tcp");
+            assert_eq!(fds[1].name(), "unix");
+            assert_eq!(fds[0].as_fd().as_raw_fd(), LISTEN_FDS_START);
+            assert!(fds.iter().all(is_cloexec));
+
+            let mut fds = fds.into_iter();
+            let tcp: TcpListener = fds.next().unwrap().into_socketlike();
+            let unix: UnixListener = fds.next().unwrap().into_socketlike();
+            let tcp_addr: SocketAddr = env::var("TCP_ADDR").unwrap().parse().unwrap();
+            assert_eq!(tcp.local_addr().unwrap(), tcp_addr);
+            assert_eq!(
+                unix.local_addr().unwrap().as_pathname(),
+                Some(PathBuf::from(env::var("UNIX_PATH").unwrap()).as_path())
+            );
+
+            // The file descriptors are only adopted once.
+            assert!(listen_fds().unwrap().is_empty());
+        }
+        "unnamed" => {
+            let fds = listen_fds().unwrap();
+            assert_eq!(fds.len(), 1);
+            assert_eq!(fds[0].name(), "unknown");
+        }
+        "other-pid" => {
+            env::set_var("LISTEN_PID", "1");
+            assert!(listen_fds().unwrap().is_empty());
+            assert_env_removed();
+
+            // The file descriptor wasn't adopted, so it's still open.
+            assert_ne!(unsafe { libc::fcntl(LISTEN_FDS_START, libc::F_GETFD) }, -1);
+        }
+        "bad-names" | "not-open" | "bad-count" => {
+            let err = listen_fds().unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+            assert_env_removed();
+        }
+        _ => panic!("unknown scenario {}", scenario),
+    }
+}
+
+#[test]
+fn test_listeners() {
+    let tcp = TcpListener::bind("127.0.0.1:0").unwrap();
+    let unix_path = env::temp_dir().join(format!("io-lifetimes-activation-{}", std::process::id()));
+    let _ = std::fs::remove_file(&unix_path);
+    let unix = UnixListener::bind(&unix_path).unwrap();
+
+    spawn_child("listeners", |mut command| {
+        command
+            .env("LISTEN_FDS", "2")
+            .env("LISTEN_FDNAMES", "tcp:unix")
+            .env("TCP_ADDR", tcp.local_addr().unwrap().to_string())
+            .env("UNIX_PATH", &unix_path);
+        command
+            .map_fd(&tcp, 3)
+            .map_fd(&unix, 4)
+            .status()
+            .unwrap()
+            .success()
+    });
+
+    std::fs::remove_file(&unix_path).unwrap();
+}
+
+#[test]
+fn test_unnamed() {
+    let tcp = TcpListener::bind("127.0.0.1:0").unwrap();
+    spawn_child("unnamed", |mut command| {
+        command.env("LISTEN_FDS", "1");
+        command.map_fd(&tcp, 3).status().unwrap().success()
+    });
+}
+
+#[test]
+fn test_other_pid() {
+    let file = File::open("Cargo.toml").unwrap();
+    spawn_child("other-pid", |mut command| {
+        command.env("LISTEN_FDS", "1");
+        command.map_fd(&file, 3).status().unwrap().success()
+    });
+}
+
+#[test]
+fn test_invalid() {
+    let file = File::open("Cargo.toml").unwrap();
+    spawn_child("bad-names", |mut command| {
+        command.env("LISTEN_FDS", "1").env("LISTEN_FDNAMES", "a:b");
+        command.map_fd(&file, 3).status().unwrap().success()
+    });
+    spawn_child("not-open", |mut command| {
+        command.env("LISTEN_FDS", "100");
+        command.map_fd(&file, 3).status().unwrap().success()
+    });
+    // A huge count fails at the first number which isn't open, without
+    // allocating a name for each file descriptor first.
+    spawn_child("not-open", |mut command| {
+        command.env("LISTEN_FDS", "2000000000");
+        command.map_fd(&file, 3).status().unwrap().success()
+    });
+    spawn_child("bad-count", |mut command| {
+        command.env("LISTEN_FDS", "-1").status().unwrap().success()
+    });
+}
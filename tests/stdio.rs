@@ -0,0 +1,110 @@
+#![cfg(all(unix, feature = "close"))]
+
+use io_lifetimes::stdio::{StdioRedirect, StdioStream};
+use io_lifetimes::{AsFilelike, BorrowedFd};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::os::unix::fs::MetadataExt;
+use std::panic::catch_unwind;
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+
+// Redirects affect the whole process, so the tests mustn't run concurrently.
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "io-lifetimes-stdio-{}-{}",
+        std::process::id(),
+        name
+    ))
+}
+
+/// Return the device and inode of the file open at `stream`.
+fn identity(stream: StdioStream) -> (u64, u64) {
+    let fd = unsafe { BorrowedFd::borrow_raw(stream.as_raw_filelike()) };
+    let metadata = fd.as_filelike_view::<File>().metadata().unwrap();
+    (metadata.dev(), metadata.ino())
+}
+
+#[test]
+fn test_redirect_stdout() {
+    let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let path = temp_path("stdout");
+    let original = identity(StdioStream::Stdout);
+
+    let redirect = StdioRedirect::stdout(File::create(&path).unwrap()).unwrap();
+    assert_eq!(redirect.stream(), StdioStream::Stdout);
+    assert_ne!(identity(StdioStream::Stdout), original);
+
+    // Write a partial line, which `stdout` buffers, and a line written
+    // directly to the file descriptor, as a C library might.
+    write!(io::stdout(), "buffered").unwrap();
+    let direct = b" direct\n";
+    assert_eq!(
+        unsafe { libc::write(1, direct.as_ptr().cast(), direct.len()) },
+        direct.len() as isize
+    );
+
+    // Restoring flushes the buffered output to the redirect target.
+    redirect.restore().unwrap();
+    assert_eq!(identity(StdioStream::Stdout), original);
+
+    let contents = fs::read_to_string(&path).unwrap();
+    assert!(contents.contains(" direct\nbuffered"), "{:?}", contents);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_redirect_stderr_panic() {
+    let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let path = temp_path("stderr");
+    let original = identity(StdioStream::Stderr);
+
+    let file = File::create(&path).unwrap();
+    catch_unwind(|| {
+        let _redirect = StdioRedirect::stderr(&file).unwrap();
+        writeln!(io::stderr(), "before panic").unwrap();
+        panic!("unwinding through a redirect");
+    })
+    .unwrap_err();
+
+    // The guard restored stderr while unwinding.
+    assert_eq!(identity(StdioStream::Stderr), original);
+    // With `--nocapture`, the panic message is written to the redirected
+    // stderr too, after our line.
+    let contents = fs::read_to_string(&path).unwrap();
+    assert!(contents.starts_with("before panic\n"), "{:?}", contents);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_redirect_stdin_nested() {
+    let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let outer_path = temp_path("stdin-outer");
+    let inner_path = temp_path("stdin-inner");
+    fs::write(&outer_path, "outer").unwrap();
+    fs::write(&inner_path, "inner").unwrap();
+    let original = identity(StdioStream::Stdin);
+
+    let read_stdin = || {
+        let fd = unsafe { BorrowedFd::borrow_raw(0) };
+        let mut s = String::new();
+        (&*fd.as_filelike_view::<File>())
+            .read_to_string(&mut s)
+            .unwrap();
+        s
+    };
+
+    let outer = StdioRedirect::stdin(File::open(&outer_path).unwrap()).unwrap();
+    {
+        let _inner = StdioRedirect::stdin(File::open(&inner_path).unwrap()).unwrap();
+        assert_eq!(read_stdin(), "inner");
+    }
+    assert_eq!(read_stdin(), "outer");
+    drop(outer);
+    assert_eq!(identity(StdioStream::Stdin), original);
+
+    fs::remove_file(&outer_path).unwrap();
+    fs::remove_file(&inner_path).unwrap();
+}
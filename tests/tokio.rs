@@ -0,0 +1,149 @@
+#![cfg(all(feature = "tokio", not(target_os = "wasi")))]
+
+use io_lifetimes::raw::AsRawSocketlike;
+use io_lifetimes::views::{AsyncFilelikeViewType, AsyncSocketlikeViewType};
+use io_lifetimes::{IntoFilelike, IntoSocketlike};
+use std::future::Future;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[test]
+fn test_tokio_tcp() {
+    block_on(async {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let raw = listener.as_raw_socketlike();
+
+        let listener =
+            tokio::net::TcpListener::from_socketlike_registered(listener.into_socketlike())
+                .unwrap();
+        let mut client = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let (server, _) = listener.accept().await.unwrap();
+
+        // Round-trip the accepted stream through an `OwnedFd` and back.
+        let server_raw = server.as_raw_socketlike();
+        let owned = server.into_socketlike_deregistered().unwrap();
+        assert_eq!(owned.as_raw_socketlike(), server_raw);
+        let mut server = tokio::net::TcpStream::from_socketlike_registered(owned).unwrap();
+
+        client.write_all(b"hello").await.unwrap();
+        let mut buf = [0_u8; 5];
+        server.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"hello");
+
+        let owned = listener.into_socketlike_deregistered().unwrap();
+        assert_eq!(owned.as_raw_socketlike(), raw);
+    });
+}
+
+#[test]
+fn test_tokio_udp() {
+    block_on(async {
+        let a = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let b = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let b_addr = b.local_addr().unwrap();
+
+        let a = tokio::net::UdpSocket::from_socketlike_registered(a.into_socketlike()).unwrap();
+        let b = tokio::net::UdpSocket::from_socketlike_registered(b.into_socketlike()).unwrap();
+
+        a.send_to(b"hello", b_addr).await.unwrap();
+        let mut buf = [0_u8; 5];
+        let (n, _) = b.recv_from(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"hello");
+
+        let _ = a.into_socketlike_deregistered().unwrap();
+        let _ = b.into_socketlike_deregistered().unwrap();
+    });
+}
+
+#[cfg(unix)]
+#[test]
+fn test_tokio_unix() {
+    block_on(async {
+        let (a, b) = std::os::unix::net::UnixStream::pair().unwrap();
+        let raw = a.as_raw_socketlike();
+
+        let mut a =
+            tokio::net::UnixStream::from_socketlike_registered(a.into_socketlike()).unwrap();
+        let mut b =
+            tokio::net::UnixStream::from_socketlike_registered(b.into_socketlike()).unwrap();
+
+        a.write_all(b"hello").await.unwrap();
+        let mut buf = [0_u8; 5];
+        b.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"hello");
+
+        let owned = a.into_socketlike_deregistered().unwrap();
+        assert_eq!(owned.as_raw_socketlike(), raw);
+
+        let (c, d) = std::os::unix::net::UnixDatagram::pair().unwrap();
+        let c = tokio::net::UnixDatagram::from_socketlike_registered(c.into_socketlike()).unwrap();
+        let d = tokio::net::UnixDatagram::from_socketlike_registered(d.into_socketlike()).unwrap();
+        c.send(b"hello").await.unwrap();
+        let n = d.recv(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"hello");
+
+        let path = std::env::temp_dir().join(format!("io-lifetimes-tokio-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        let listener =
+            tokio::net::UnixListener::from_socketlike_registered(listener.into_socketlike())
+                .unwrap();
+        let _client = tokio::net::UnixStream::connect(&path).await.unwrap();
+        let _ = listener.accept().await.unwrap();
+        let _ = listener.into_socketlike_deregistered().unwrap();
+        std::fs::remove_file(&path).unwrap();
+    });
+}
+
+#[test]
+fn test_tokio_file() {
+    block_on(async {
+        let file = std::fs::File::open("Cargo.toml").unwrap();
+        let mut file = tokio::fs::File::from_filelike_registered(file.into_filelike()).unwrap();
+
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).await.unwrap();
+        assert!(contents.contains("io-lifetimes"));
+
+        let _ = file.into_filelike_deregistered().unwrap();
+    });
+}
+
+#[cfg(unix)]
+#[test]
+fn test_tokio_process() {
+    block_on(async {
+        let mut child = std::process::Command::new("cat")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let mut stdin = tokio::process::ChildStdin::from_filelike_registered(
+            child.stdin.take().unwrap().into_filelike(),
+        )
+        .unwrap();
+        let mut stdout = tokio::process::ChildStdout::from_filelike_registered(
+            child.stdout.take().unwrap().into_filelike(),
+        )
+        .unwrap();
+
+        stdin.write_all(b"hello").await.unwrap();
+        drop(stdin.into_filelike_deregistered().unwrap());
+
+        let mut contents = Vec::new();
+        stdout.read_to_end(&mut contents).await.unwrap();
+        assert_eq!(contents, b"hello");
+        let _ = stdout.into_filelike_deregistered().unwrap();
+
+        assert!(child.wait().unwrap().success());
+    });
+}
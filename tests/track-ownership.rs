@@ -0,0 +1,137 @@
+#![cfg(all(unix, feature = "track-ownership"))]
+
+use io_lifetimes::raw::{AsRawFilelike, FromRawFilelike};
+use io_lifetimes::track::{set_strict_borrows, take_reports, ReportKind};
+use io_lifetimes::views::FilelikeView;
+use io_lifetimes::{AsFilelike, CloseChecked, FromFilelike, IntoFilelike, OwnedFilelike};
+use std::fs::File;
+use std::os::unix::io::{AsRawFd, IntoRawFd};
+use std::os::unix::net::UnixStream;
+use std::sync::Mutex;
+
+// The tracker is process-wide, so the tests mustn't run concurrently.
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn reports_for(raw: i32) -> Vec<ReportKind> {
+    take_reports()
+        .into_iter()
+        .filter(|report| report.raw_fd() == raw)
+        .map(|report| {
+            // Borrows in strict mode don't conflict with anything.
+            if report.kind() != ReportKind::UnregisteredBorrow {
+                assert!(report.previous().is_some(), "{}", report);
+            }
+            report.kind()
+        })
+        .collect()
+}
+
+#[test]
+fn test_round_trip_is_clean() {
+    let _lock = LOCK.lock().unwrap();
+    take_reports();
+
+    let file = File::from_into_filelike(File::open("Cargo.toml").unwrap());
+    let raw = file.as_raw_filelike();
+    let file = File::from_into_filelike(file);
+    drop(file.as_filelike_view::<File>());
+    file.close().unwrap();
+
+    assert_eq!(reports_for(raw), []);
+}
+
+#[test]
+fn test_duplicate_claim() {
+    let _lock = LOCK.lock().unwrap();
+    take_reports();
+
+    let (a, _b) = UnixStream::pair().unwrap();
+    let a = UnixStream::from_filelike(a.into_filelike());
+    let raw = a.as_raw_filelike();
+    let dup = UnixStream::from_filelike(unsafe { OwnedFilelike::from_raw_filelike(raw) });
+
+    assert_eq!(reports_for(raw), [ReportKind::DuplicateClaim]);
+    std::mem::forget(dup);
+}
+
+#[test]
+fn test_reacquire_after_drop() {
+    let _lock = LOCK.lock().unwrap();
+    take_reports();
+
+    // Closing an owner's socket isn't visible to the tracker, so its record
+    // is stale, but acquiring the number again isn't a duplicate claim.
+    // Replace the socket with `dup2`, which closes it and reuses its number
+    // in one step, rather than relying on a new socket getting the number.
+    let (a, _b) = UnixStream::pair().unwrap();
+    let a = UnixStream::from_filelike(a.into_filelike());
+    let raw = a.into_raw_fd();
+    let (c, _d) = UnixStream::pair().unwrap();
+    assert_eq!(unsafe { libc::dup2(c.as_raw_fd(), raw) }, raw);
+    drop(c);
+
+    let _a = UnixStream::from_filelike(unsafe { OwnedFilelike::from_raw_filelike(raw) });
+
+    assert_eq!(reports_for(raw), []);
+}
+
+#[test]
+fn test_double_close() {
+    let _lock = LOCK.lock().unwrap();
+    take_reports();
+
+    let file = File::open("Cargo.toml").unwrap();
+    let raw = file.as_raw_filelike();
+    let dup = unsafe { OwnedFilelike::from_raw_filelike(raw) };
+
+    file.close().unwrap();
+    assert!(dup.close().is_err());
+
+    assert_eq!(reports_for(raw), [ReportKind::DoubleClose]);
+}
+
+#[test]
+fn test_use_after_close() {
+    let _lock = LOCK.lock().unwrap();
+    take_reports();
+
+    let file = File::open("Cargo.toml").unwrap();
+    let raw = file.as_raw_filelike();
+    file.close().unwrap();
+
+    drop(unsafe { FilelikeView::<File>::view_raw(raw) });
+
+    assert_eq!(reports_for(raw), [ReportKind::UseAfterClose]);
+}
+
+#[test]
+fn test_strict_borrows() {
+    let _lock = LOCK.lock().unwrap();
+    take_reports();
+
+    // Turn strict mode off again even if the test fails.
+    struct Strict;
+    impl Drop for Strict {
+        fn drop(&mut self) {
+            set_strict_borrows(false);
+        }
+    }
+
+    let file = File::open("Cargo.toml").unwrap();
+    let raw = file.as_raw_filelike();
+
+    // By default, borrowing a file descriptor the tracker hasn't seen
+    // acquired is fine.
+    drop(unsafe { FilelikeView::<File>::view_raw(raw) });
+    assert_eq!(reports_for(raw), []);
+
+    // In strict mode, it's reported, until the file descriptor is acquired.
+    set_strict_borrows(true);
+    let _strict = Strict;
+    drop(unsafe { FilelikeView::<File>::view_raw(raw) });
+    let file = File::from_into_filelike(file);
+    drop(unsafe { FilelikeView::<File>::view_raw(raw) });
+
+    assert_eq!(reports_for(raw), [ReportKind::UnregisteredBorrow]);
+    file.close().unwrap();
+}
@@ -0,0 +1,159 @@
+#![cfg(all(unix, feature = "close"))]
+
+use io_lifetimes::typed::{
+    DatagramSocket, Directory, ListeningSocket, Pipe, RegularFile, StreamSocket, TypedBorrowed,
+    TypedOwned,
+};
+use io_lifetimes::views::TryViewError;
+use io_lifetimes::{AsFd, OwnedFd};
+use std::ffi::CString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream, UdpSocket};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::FromRawFd;
+use std::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::process::{ChildStdin, Command, Stdio};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "io-lifetimes-typed-{}-{}",
+        std::process::id(),
+        name
+    ))
+}
+
+#[test]
+fn test_files_and_directories() {
+    let file = File::open("Cargo.toml").unwrap();
+    let dir = File::open("src").unwrap();
+
+    // Both are opened with `File`, but only one of them is a directory.
+    assert!(TypedBorrowed::<RegularFile>::try_from(file.as_fd()).is_ok());
+    assert!(TypedBorrowed::<Directory>::try_from(dir.as_fd()).is_ok());
+    let err = TypedBorrowed::<RegularFile>::try_from(dir.as_fd()).unwrap_err();
+    assert!(matches!(
+        err.error(),
+        TryViewError::Mismatch {
+            target: "RegularFile",
+            ..
+        }
+    ));
+
+    let typed = TypedOwned::<Directory>::try_from_filelike(dir.into()).unwrap();
+    assert!(typed.filelike_view::<File>().metadata().unwrap().is_dir());
+}
+
+#[test]
+fn test_pipes_and_fifos() {
+    // The standard I/O pipes of a child process are pipes.
+    let mut child = Command::new("cat")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let stdin = TypedOwned::<Pipe>::from(child.stdin.take().unwrap());
+    let stdout = TypedOwned::<Pipe>::from(child.stdout.take().unwrap());
+    assert!(TypedBorrowed::<Pipe>::try_from(stdin.as_fd()).is_ok());
+    assert!(TypedBorrowed::<RegularFile>::try_from(stdout.as_fd()).is_err());
+
+    (&*stdin.filelike_view::<File>()).write_all(b"cat").unwrap();
+    drop(stdin);
+    let mut s = String::new();
+    (&*stdout.filelike_view::<File>())
+        .read_to_string(&mut s)
+        .unwrap();
+    assert_eq!(s, "cat");
+    child.wait().unwrap();
+
+    // A FIFO is a pipe too, even though it's opened by name.
+    let path = temp_path("fifo");
+    let c_path = CString::new(path.as_os_str().as_bytes()).unwrap();
+    assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+    let fifo = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(&path)
+        .unwrap();
+    assert!(TypedBorrowed::<Pipe>::try_from(fifo.as_fd()).is_ok());
+    assert!(TypedBorrowed::<RegularFile>::try_from(fifo.as_fd()).is_err());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_stream_sockets() {
+    // Stream sockets of either address family are stream sockets.
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let tcp = TcpStream::connect(tcp_listener.local_addr().unwrap()).unwrap();
+    let (unix, _peer) = UnixStream::pair().unwrap();
+    assert!(TypedBorrowed::<StreamSocket>::try_from_socketlike(tcp.as_fd()).is_ok());
+    assert!(TypedBorrowed::<StreamSocket>::try_from_socketlike(unix.as_fd()).is_ok());
+    assert!(TypedBorrowed::<DatagramSocket>::try_from_socketlike(tcp.as_fd()).is_err());
+
+    let tcp = TypedOwned::<StreamSocket>::try_from_socketlike(tcp.into()).unwrap();
+    assert_eq!(
+        tcp.socketlike_view::<TcpStream>().peer_addr().unwrap(),
+        tcp_listener.local_addr().unwrap()
+    );
+
+    // The address family isn't tracked, so a Unix-domain stream socket can
+    // be viewed as a `TcpStream`, but it doesn't have a TCP peer address.
+    let unix = TypedOwned::<StreamSocket>::from(unix);
+    assert!(unix.socketlike_view::<TcpStream>().peer_addr().is_err());
+    assert!(unix.socketlike_view::<UnixStream>().peer_addr().is_ok());
+}
+
+#[test]
+fn test_unconnected_and_listening_sockets() {
+    let raw = unsafe { libc::socket(libc::AF_INET, libc::SOCK_STREAM, 0) };
+    assert_ne!(raw, -1);
+    let socket = unsafe { OwnedFd::from_raw_fd(raw) };
+
+    // A stream socket which hasn't been connected yet is a stream socket,
+    // until it starts listening.
+    assert!(TypedBorrowed::<StreamSocket>::try_from(socket.as_fd()).is_ok());
+    assert!(TypedBorrowed::<ListeningSocket>::try_from(socket.as_fd()).is_err());
+    assert_eq!(unsafe { libc::listen(raw, 1) }, 0);
+    assert!(TypedBorrowed::<StreamSocket>::try_from(socket.as_fd()).is_err());
+    assert!(TypedBorrowed::<ListeningSocket>::try_from(socket.as_fd()).is_ok());
+
+    // Listening sockets of either address family are listening sockets.
+    let path = temp_path("listener");
+    let unix_listener = UnixListener::bind(&path).unwrap();
+    let listening = TypedBorrowed::<ListeningSocket>::from(&unix_listener);
+    assert_eq!(
+        listening
+            .socketlike_view::<UnixListener>()
+            .local_addr()
+            .unwrap()
+            .as_pathname(),
+        Some(path.as_path())
+    );
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_datagram_sockets() {
+    let udp = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let (unix, _peer) = UnixDatagram::pair().unwrap();
+    assert!(TypedBorrowed::<DatagramSocket>::try_from(udp.as_fd()).is_ok());
+    assert!(TypedBorrowed::<DatagramSocket>::try_from(unix.as_fd()).is_ok());
+    assert!(TypedBorrowed::<StreamSocket>::try_from(udp.as_fd()).is_err());
+    assert!(TypedBorrowed::<ListeningSocket>::try_from(unix.as_fd()).is_err());
+}
+
+#[test]
+fn test_kind_is_best_effort() {
+    // `ChildStdin` can be constructed from any file descriptor, so its
+    // conversion can wrap a file which isn't a pipe.
+    let file = File::open("Cargo.toml").unwrap();
+    let stdin = ChildStdin::from(OwnedFd::from(file));
+    let pipe = TypedOwned::<Pipe>::from(stdin);
+
+    // Checking it again reports the mismatch, as an `io::Error` too.
+    let err = TypedOwned::<Pipe>::try_from(OwnedFd::from(pipe)).unwrap_err();
+    assert!(err.to_string().contains("not a pipe"), "{}", err);
+    let err: io::Error = err.into();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
@@ -0,0 +1,63 @@
+#![cfg(not(target_os = "wasi"))]
+
+use io_lifetimes::raw::{
+    with_borrowed_raw_filelike, with_borrowed_raw_socketlike, with_raw_filelike_view,
+    with_raw_socketlike_view, AsRawFilelike, AsRawSocketlike,
+};
+use io_lifetimes::{AsFilelike, AsSocketlike};
+use std::fs::File;
+use std::io::Read;
+use std::net::{TcpListener, TcpStream};
+
+#[test]
+fn test_with_borrowed_raw_filelike() {
+    let file = File::open("Cargo.toml").unwrap();
+    let raw = file.as_raw_filelike();
+
+    let same = unsafe { with_borrowed_raw_filelike(raw, |fd| fd.as_raw_filelike() == raw) };
+    assert!(same);
+
+    let mut s = String::new();
+    unsafe {
+        with_borrowed_raw_filelike(raw, |fd| {
+            (&*fd.as_filelike_view::<File>()).read_to_string(&mut s)
+        })
+    }
+    .unwrap();
+    assert!(s.contains("io-lifetimes"));
+}
+
+#[test]
+fn test_with_borrowed_raw_socketlike() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let raw = listener.as_raw_socketlike();
+
+    let local = unsafe {
+        with_borrowed_raw_socketlike(raw, |socket| {
+            socket.as_socketlike_view::<TcpListener>().local_addr()
+        })
+    };
+    assert_eq!(local.unwrap(), addr);
+}
+
+#[test]
+fn test_with_raw_views() {
+    let file = File::open("Cargo.toml").unwrap();
+    let len = unsafe { with_raw_filelike_view(file.as_raw_filelike(), |f: &File| f.metadata()) }
+        .unwrap()
+        .len();
+    assert_eq!(len, file.metadata().unwrap().len());
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let _client = TcpStream::connect(addr).unwrap();
+    let (_server, peer) = unsafe {
+        with_raw_socketlike_view(listener.as_raw_socketlike(), |l: &TcpListener| l.accept())
+    }
+    .unwrap();
+    assert_eq!(peer.ip(), addr.ip());
+
+    // The view didn't close the listener.
+    assert_eq!(listener.local_addr().unwrap(), addr);
+}